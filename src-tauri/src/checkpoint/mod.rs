@@ -140,6 +140,23 @@ pub struct CheckpointResult {
     pub warnings: Vec<String>,
 }
 
+/// Storage statistics for a single checkpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckpointStats {
+    /// Checkpoint identifier
+    pub id: String,
+    /// User-provided description of the checkpoint
+    pub label: Option<String>,
+    /// When the checkpoint was created
+    pub created_at: DateTime<Utc>,
+    /// Number of files captured in the checkpoint
+    pub file_count: usize,
+    /// Uncompressed size of the captured files in bytes
+    pub total_bytes: u64,
+    /// On-disk size of the checkpoint's messages and file contents in bytes
+    pub compressed: u64,
+}
+
 /// Diff between two checkpoints
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CheckpointDiff {
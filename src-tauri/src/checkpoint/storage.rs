@@ -1,4 +1,5 @@
 use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -6,15 +7,40 @@ use uuid::Uuid;
 use zstd::stream::{decode_all, encode_all};
 
 use super::{
-    Checkpoint, CheckpointPaths, CheckpointResult, FileSnapshot, SessionTimeline, TimelineNode,
+    Checkpoint, CheckpointPaths, CheckpointResult, CheckpointStats, FileSnapshot, SessionTimeline,
+    TimelineNode,
 };
 
+/// Format identifier written into checkpoint archives
+const CHECKPOINT_ARCHIVE_FORMAT: &str = "opcode-checkpoint";
+
+/// Schema version of checkpoint archives; archives with a different version are rejected
+const CHECKPOINT_ARCHIVE_VERSION: u32 = 1;
+
+/// Self-contained bundle of a checkpoint, its messages and file snapshots
+#[derive(Debug, Serialize, Deserialize)]
+struct CheckpointArchive {
+    format: String,
+    version: u32,
+    checkpoint: Checkpoint,
+    messages: String,
+    file_snapshots: Vec<FileSnapshot>,
+}
+
 /// Manages checkpoint storage operations
 pub struct CheckpointStorage {
     pub claude_dir: PathBuf,
     compression_level: i32,
 }
 
+/// Whether a snapshot path is relative and stays inside the project root
+fn is_safe_snapshot_path(path: &Path) -> bool {
+    !path.as_os_str().is_empty()
+        && path
+            .components()
+            .all(|component| matches!(component, std::path::Component::Normal(_)))
+}
+
 impl CheckpointStorage {
     /// Create a new checkpoint storage instance
     pub fn new(claude_dir: PathBuf) -> Self {
@@ -312,6 +338,98 @@ impl CheckpointStorage {
         anyhow::bail!("Parent checkpoint not found: {}", parent_id)
     }
 
+    /// Export a checkpoint into a single zstd-compressed archive file
+    pub fn export_checkpoint(
+        &self,
+        project_id: &str,
+        session_id: &str,
+        checkpoint_id: &str,
+        dest: &Path,
+    ) -> Result<()> {
+        let (checkpoint, file_snapshots, messages) =
+            self.load_checkpoint(project_id, session_id, checkpoint_id)?;
+
+        let archive = CheckpointArchive {
+            format: CHECKPOINT_ARCHIVE_FORMAT.to_string(),
+            version: CHECKPOINT_ARCHIVE_VERSION,
+            checkpoint,
+            messages,
+            file_snapshots,
+        };
+
+        let archive_json =
+            serde_json::to_vec(&archive).context("Failed to serialize checkpoint archive")?;
+        let compressed = encode_all(&archive_json[..], self.compression_level)
+            .context("Failed to compress checkpoint archive")?;
+        fs::write(dest, compressed).context("Failed to write checkpoint archive")?;
+
+        Ok(())
+    }
+
+    /// Import a checkpoint archive into the local store under a fresh checkpoint ID
+    ///
+    /// The checkpoint is attached to the session's current checkpoint (or becomes the
+    /// root of an empty timeline), keeping its original description.
+    pub fn import_checkpoint(
+        &self,
+        archive_path: &Path,
+        project_id: &str,
+        session_id: &str,
+    ) -> Result<CheckpointResult> {
+        let compressed = fs::read(archive_path).context("Failed to read checkpoint archive")?;
+        let archive_json = decode_all(&compressed[..]).context("Not a valid checkpoint archive")?;
+        let archive: CheckpointArchive =
+            serde_json::from_slice(&archive_json).context("Failed to parse checkpoint archive")?;
+
+        if archive.format != CHECKPOINT_ARCHIVE_FORMAT {
+            anyhow::bail!("Unrecognized checkpoint archive format: {}", archive.format);
+        }
+        if archive.version != CHECKPOINT_ARCHIVE_VERSION {
+            anyhow::bail!(
+                "Unsupported checkpoint archive version {} (expected {})",
+                archive.version,
+                CHECKPOINT_ARCHIVE_VERSION
+            );
+        }
+
+        self.init_storage(project_id, session_id)?;
+        let paths = CheckpointPaths::new(&self.claude_dir, project_id, session_id);
+        let timeline = self.load_timeline(&paths.timeline_file)?;
+
+        let new_id = Self::generate_checkpoint_id();
+        let mut checkpoint = archive.checkpoint;
+        checkpoint.id = new_id.clone();
+        checkpoint.session_id = session_id.to_string();
+        checkpoint.project_id = project_id.to_string();
+        checkpoint.parent_checkpoint_id = timeline.current_checkpoint_id;
+
+        // Archive contents are untrusted: the hash names a file in the content pool and
+        // the path is written on restore, so neither may escape its directory
+        let file_snapshots = archive
+            .file_snapshots
+            .into_iter()
+            .map(|mut snapshot| {
+                if !is_safe_snapshot_path(&snapshot.file_path) {
+                    anyhow::bail!(
+                        "Checkpoint archive contains an unsafe file path: {}",
+                        snapshot.file_path.display()
+                    );
+                }
+                snapshot.hash = Self::calculate_file_hash(&snapshot.content);
+                snapshot.checkpoint_id = new_id.clone();
+                Ok(snapshot)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        self.save_checkpoint(
+            project_id,
+            session_id,
+            &checkpoint,
+            file_snapshots,
+            &archive.messages,
+        )
+    }
+
     /// Calculate hash of file content
     pub fn calculate_file_hash(content: &str) -> String {
         let mut hasher = Sha256::new();
@@ -377,6 +495,73 @@ impl CheckpointStorage {
         Ok(removed_count)
     }
 
+    /// List all checkpoints of a session with their file counts and storage sizes
+    ///
+    /// Sizes are computed from the stored file references and content pool without
+    /// decompressing any content. Shared content is counted for every checkpoint using it.
+    pub fn list_checkpoints_with_stats(
+        &self,
+        project_id: &str,
+        session_id: &str,
+    ) -> Result<Vec<CheckpointStats>> {
+        let paths = CheckpointPaths::new(&self.claude_dir, project_id, session_id);
+        if !paths.timeline_file.exists() {
+            return Ok(Vec::new());
+        }
+        let timeline = self.load_timeline(&paths.timeline_file)?;
+
+        let mut checkpoints = Vec::new();
+        if let Some(root) = &timeline.root_node {
+            Self::collect_checkpoints(root, &mut checkpoints);
+        }
+        checkpoints.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+
+        let content_pool_dir = paths.files_dir.join("content_pool");
+        let mut stats = Vec::with_capacity(checkpoints.len());
+
+        for checkpoint in checkpoints {
+            let mut file_count = 0;
+            let mut total_bytes = 0;
+            let mut compressed = fs::metadata(paths.checkpoint_messages_file(&checkpoint.id))
+                .map(|m| m.len())
+                .unwrap_or(0);
+
+            let refs_dir = paths.files_dir.join("refs").join(&checkpoint.id);
+            if refs_dir.exists() {
+                for entry in fs::read_dir(&refs_dir)? {
+                    let ref_path = entry?.path();
+                    if ref_path.extension().and_then(|e| e.to_str()) != Some("json") {
+                        continue;
+                    }
+
+                    let ref_json =
+                        fs::read_to_string(&ref_path).context("Failed to read file reference")?;
+                    let ref_metadata: serde_json::Value = serde_json::from_str(&ref_json)
+                        .context("Failed to parse file reference")?;
+
+                    file_count += 1;
+                    total_bytes += ref_metadata["size"].as_u64().unwrap_or(0);
+                    if let Some(hash) = ref_metadata["hash"].as_str() {
+                        compressed += fs::metadata(content_pool_dir.join(hash))
+                            .map(|m| m.len())
+                            .unwrap_or(0);
+                    }
+                }
+            }
+
+            stats.push(CheckpointStats {
+                id: checkpoint.id,
+                label: checkpoint.description,
+                created_at: checkpoint.timestamp,
+                file_count,
+                total_bytes,
+                compressed,
+            });
+        }
+
+        Ok(stats)
+    }
+
     /// Collect all checkpoints from the tree in order
     fn collect_checkpoints(node: &TimelineNode, checkpoints: &mut Vec<Checkpoint>) {
         checkpoints.push(node.checkpoint.clone());
@@ -5,8 +5,9 @@ use std::cmp::Ordering;
 /// Shared module for detecting Claude Code binary installations
 /// Supports NVM installations, aliased paths, and version-based selection
 use std::path::PathBuf;
-use std::process::Command;
-use tauri::Manager;
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
 /// Type of Claude installation
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -24,10 +25,36 @@ pub struct ClaudeInstallation {
     pub path: String,
     /// Version string if available
     pub version: Option<String>,
+    /// The `--version` probe timed out, so `version` is unknown rather than absent
+    #[serde(default)]
+    pub version_check_timed_out: bool,
     /// Source of discovery (e.g., "nvm", "system", "homebrew", "which")
     pub source: String,
     /// Type of installation
     pub installation_type: InstallationType,
+    /// Node.js version the installation runs on, when it is a Node install and known
+    #[serde(default)]
+    pub node_version: Option<String>,
+}
+
+/// How long a discovered installation is reused before discovery runs again
+const DISCOVERY_CACHE_TTL: Duration = Duration::from_secs(30);
+
+/// Most recent discovery result, keyed by the selection preference it was made with
+struct CachedDiscovery {
+    resolved_at: Instant,
+    prefer_stable: bool,
+    extra_binary_names: Vec<String>,
+    path: String,
+}
+
+/// Shared discovery cache; its lock also coalesces concurrent discoveries
+static DISCOVERY_CACHE: Mutex<Option<CachedDiscovery>> = Mutex::new(None);
+
+/// Forget the cached installation so the next lookup runs full discovery
+pub fn invalidate_claude_binary_cache() {
+    let mut cache = DISCOVERY_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    *cache = None;
 }
 
 /// Main function to find the Claude binary
@@ -35,8 +62,11 @@ pub struct ClaudeInstallation {
 pub fn find_claude_binary(app_handle: &tauri::AppHandle) -> Result<String, String> {
     info!("Searching for claude binary...");
 
+    let mut prefer_stable = false;
+    let mut extra_binary_names = Vec::new();
+
     // First check if we have a stored path and preference in the database
-    if let Ok(app_data_dir) = app_handle.path().app_data_dir() {
+    if let Ok(app_data_dir) = crate::commands::storage::app_data_dir(app_handle) {
         let db_path = app_data_dir.join("agents.db");
         if db_path.exists() {
             if let Ok(conn) = rusqlite::Connection::open(&db_path) {
@@ -65,12 +95,43 @@ pub fn find_claude_binary(app_handle: &tauri::AppHandle) -> Result<String, Strin
                 ).unwrap_or_else(|_| "system".to_string());
 
                 info!("User preference for Claude installation: {}", preference);
+
+                // Check whether prereleases should only be used as a last resort
+                prefer_stable = conn
+                    .query_row(
+                        "SELECT value FROM app_settings WHERE key = 'claude_prefer_stable'",
+                        [],
+                        |row| row.get::<_, String>(0),
+                    )
+                    .map(|value| value == "true")
+                    .unwrap_or(false);
+
+                // Additional binary names (renamed binaries, wrappers) to discover
+                if let Ok(value) = conn.query_row(
+                    "SELECT value FROM app_settings WHERE key = 'claude_extra_binary_names'",
+                    [],
+                    |row| row.get::<_, String>(0),
+                ) {
+                    extra_binary_names = parse_extra_binary_names(&value);
+                }
             }
         }
     }
 
+    // Concurrent callers wait on this lock and then reuse the first caller's result
+    let mut cache = DISCOVERY_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(cached) = cache.as_ref() {
+        if cached.prefer_stable == prefer_stable
+            && cached.extra_binary_names == extra_binary_names
+            && cached.resolved_at.elapsed() < DISCOVERY_CACHE_TTL
+        {
+            debug!("Using cached Claude installation: {}", cached.path);
+            return Ok(cached.path.clone());
+        }
+    }
+
     // Discover all available system installations
-    let installations = discover_system_installations();
+    let installations = discover_system_installations(&extra_binary_names);
 
     if installations.is_empty() {
         error!("Could not find claude binary in any location");
@@ -83,11 +144,17 @@ pub fn find_claude_binary(app_handle: &tauri::AppHandle) -> Result<String, Strin
     }
 
     // Select the best installation (highest version)
-    if let Some(best) = select_best_installation(installations) {
+    if let Some(best) = select_best_installation(installations, prefer_stable) {
         info!(
             "Selected Claude installation: path={}, version={:?}, source={}",
             best.path, best.version, best.source
         );
+        *cache = Some(CachedDiscovery {
+            resolved_at: Instant::now(),
+            prefer_stable,
+            extra_binary_names,
+            path: best.path.clone(),
+        });
         Ok(best.path)
     } else {
         Err("No valid Claude installation found".to_string())
@@ -96,10 +163,11 @@ pub fn find_claude_binary(app_handle: &tauri::AppHandle) -> Result<String, Strin
 
 /// Discovers all available Claude installations and returns them for selection
 /// This allows UI to show a version selector
-pub fn discover_claude_installations() -> Vec<ClaudeInstallation> {
+pub fn discover_claude_installations(extra_binary_names: &[String]) -> Vec<ClaudeInstallation> {
     info!("Discovering all Claude installations...");
 
-    let mut installations = discover_system_installations();
+    let mut installations = discover_system_installations(extra_binary_names);
+    populate_node_versions(&mut installations);
 
     // Sort by version (highest first), then by source preference
     installations.sort_by(|a, b| {
@@ -123,6 +191,87 @@ pub fn discover_claude_installations() -> Vec<ClaudeInstallation> {
     installations
 }
 
+/// Node version encoded in a version manager's install path (nvm, fnm, volta)
+/// or in an "nvm (vX)" discovery source
+fn node_version_from_path(path: &str, source: &str) -> Option<String> {
+    let is_version = |v: &str| v.chars().next().is_some_and(|c| c.is_ascii_digit());
+
+    if let Some(version) = source
+        .strip_prefix("nvm (")
+        .and_then(|rest| rest.strip_suffix(')'))
+        .map(|v| v.trim_start_matches('v'))
+        .filter(|v| is_version(v))
+    {
+        return Some(version.to_string());
+    }
+
+    let normalized = path.replace('\\', "/");
+    [
+        "/.nvm/versions/node/",
+        "/fnm/node-versions/",
+        "/.volta/tools/image/node/",
+    ]
+    .iter()
+    .find_map(|marker| {
+        let rest = &normalized[normalized.find(marker)? + marker.len()..];
+        let version = rest.split('/').next()?.trim_start_matches('v');
+        is_version(version).then(|| version.to_string())
+    })
+}
+
+/// Whether the binary (after resolving symlinks) is a script run by node, as npm installs are
+fn is_node_script(path: &str) -> bool {
+    use std::io::Read;
+
+    let resolved = std::fs::canonicalize(path).unwrap_or_else(|_| PathBuf::from(path));
+    let mut head = [0u8; 128];
+    let read = std::fs::File::open(&resolved)
+        .and_then(|mut file| file.read(&mut head))
+        .unwrap_or(0);
+    let head = String::from_utf8_lossy(&head[..read]);
+    head.starts_with("#!")
+        && head
+            .lines()
+            .next()
+            .is_some_and(|line| line.contains("node"))
+}
+
+/// Version of the `node` on PATH, without the leading "v"
+fn detect_node_version() -> Option<String> {
+    let node = which::which("node").ok()?;
+    let output = Command::new(node)
+        .arg("--version")
+        .stdin(Stdio::null())
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let version = String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .trim_start_matches('v')
+        .to_string();
+    (!version.is_empty()).then_some(version)
+}
+
+/// Fill in `node_version` from install paths, or from `node --version` (run at most
+/// once) for PATH installs that are node scripts. Native binaries are left as `None`.
+fn populate_node_versions(installations: &mut [ClaudeInstallation]) {
+    let mut path_node_version: Option<Option<String>> = None;
+    for installation in installations.iter_mut() {
+        installation.node_version =
+            node_version_from_path(&installation.path, &installation.source).or_else(|| {
+                if is_node_script(&installation.path) {
+                    path_node_version
+                        .get_or_insert_with(detect_node_version)
+                        .clone()
+                } else {
+                    None
+                }
+            });
+    }
+}
+
 /// Returns a preference score for installation sources (lower is better)
 fn source_preference(installation: &ClaudeInstallation) -> u8 {
     match installation.source.as_str() {
@@ -139,24 +288,134 @@ fn source_preference(installation: &ClaudeInstallation) -> u8 {
         "node-modules" => 11,
         "home-bin" => 12,
         "PATH" => 13,
-        _ => 14,
+        "custom" => 14,
+        _ => 15,
     }
 }
 
-/// Discovers all Claude installations on the system
-fn discover_system_installations() -> Vec<ClaudeInstallation> {
-    let mut installations = Vec::new();
+/// Parses the `claude_extra_binary_names` setting, a JSON array or comma-separated list
+///
+/// Names containing path separators are dropped; only bare file names are searched for.
+pub fn parse_extra_binary_names(value: &str) -> Vec<String> {
+    let names: Vec<String> = serde_json::from_str::<Vec<String>>(value)
+        .unwrap_or_else(|_| value.split(',').map(str::to_string).collect());
+
+    let mut seen = std::collections::HashSet::new();
+    names
+        .into_iter()
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty() && !name.contains('/') && !name.contains('\\'))
+        .filter(|name| seen.insert(name.clone()))
+        .collect()
+}
+
+/// Directories searched for extra binary names: the standard install locations,
+/// NVM bin directories and every entry on PATH
+fn extra_binary_search_dirs() -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = Vec::new();
+
+    #[cfg(unix)]
+    {
+        for dir in ["/usr/local/bin", "/opt/homebrew/bin", "/usr/bin", "/bin"] {
+            dirs.push(PathBuf::from(dir));
+        }
+        if let Ok(nvm_bin) = std::env::var("NVM_BIN") {
+            dirs.push(PathBuf::from(nvm_bin));
+        }
+    }
+
+    if let Some(home) = dirs::home_dir() {
+        for sub in [
+            ".claude/local",
+            ".local/bin",
+            ".npm-global/bin",
+            ".yarn/bin",
+            ".bun/bin",
+            "bin",
+            "node_modules/.bin",
+            ".config/yarn/global/node_modules/.bin",
+            "AppData/Roaming/npm",
+        ] {
+            dirs.push(home.join(sub));
+        }
+        if let Ok(entries) = std::fs::read_dir(home.join(".nvm").join("versions").join("node")) {
+            dirs.extend(entries.flatten().map(|entry| entry.path().join("bin")));
+        }
+    }
+
+    if let Some(path) = std::env::var_os("PATH") {
+        dirs.extend(std::env::split_paths(&path));
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    dirs.retain(|dir| dir.is_dir() && seen.insert(dir.clone()));
+    dirs
+}
 
-    // 1. Try 'which' command first (now works in production)
-    if let Some(installation) = try_which_command() {
-        installations.push(installation);
+/// Find binaries with the configured extra names, tagged with the "custom" source
+///
+/// A candidate only counts when it answers `--version` with a recognizable version,
+/// so unrelated executables sharing a name are not offered.
+fn find_extra_name_installations(names: &[String]) -> Vec<ClaudeInstallation> {
+    if names.is_empty() {
+        return Vec::new();
     }
 
-    // 2. Check NVM paths (includes current active NVM)
-    installations.extend(find_nvm_installations());
+    let suffixes: &[&str] = if cfg!(windows) {
+        &["", ".exe", ".cmd"]
+    } else {
+        &[""]
+    };
 
-    // 3. Check standard paths
-    installations.extend(find_standard_installations());
+    let mut installations = Vec::new();
+    for dir in extra_binary_search_dirs() {
+        for name in names {
+            for suffix in suffixes {
+                let candidate = dir.join(format!("{}{}", name, suffix));
+                if !candidate.is_file() {
+                    continue;
+                }
+                let path = candidate.to_string_lossy().to_string();
+                match get_claude_version(&path).ok().flatten() {
+                    Some(version) => {
+                        debug!("Found custom-named Claude binary: {}", path);
+                        installations.push(ClaudeInstallation {
+                            path,
+                            version: Some(version),
+                            version_check_timed_out: false,
+                            source: "custom".to_string(),
+                            installation_type: InstallationType::System,
+                            node_version: None,
+                        });
+                    }
+                    None => debug!("Ignoring {}: no Claude version reported", path),
+                }
+            }
+        }
+    }
+
+    installations
+}
+
+/// Discovers all Claude installations on the system
+fn discover_system_installations(extra_binary_names: &[String]) -> Vec<ClaudeInstallation> {
+    // Each source probes binaries with `--version`; run them concurrently so one
+    // slow binary doesn't hold up the rest. Join order keeps source priority.
+    let mut installations = std::thread::scope(|scope| {
+        // 1. Try 'which' command first (now works in production)
+        let which = scope.spawn(|| try_which_command().into_iter().collect::<Vec<_>>());
+        // 2. Check NVM paths (includes current active NVM)
+        let nvm = scope.spawn(find_nvm_installations);
+        // 3. Check standard paths
+        let standard = scope.spawn(find_standard_installations);
+        // 4. Check configured extra binary names
+        let extra = scope.spawn(|| find_extra_name_installations(extra_binary_names));
+
+        [which, nvm, standard, extra]
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap_or_default())
+            .collect::<Vec<_>>()
+    });
 
     // Remove duplicates by path
     let mut unique_paths = std::collections::HashSet::new();
@@ -197,13 +456,15 @@ fn try_which_command() -> Option<ClaudeInstallation> {
             }
 
             // Get version
-            let version = get_claude_version(&path).ok().flatten();
+            let (version, version_check_timed_out) = probe_claude_version(&path);
 
             Some(ClaudeInstallation {
                 path,
                 version,
+                version_check_timed_out,
                 source: "which".to_string(),
                 installation_type: InstallationType::System,
+                node_version: None,
             })
         }
         _ => None,
@@ -238,19 +499,205 @@ fn try_which_command() -> Option<ClaudeInstallation> {
             }
 
             // Get version
-            let version = get_claude_version(&path).ok().flatten();
+            let (version, version_check_timed_out) = probe_claude_version(&path);
 
             Some(ClaudeInstallation {
                 path,
                 version,
+                version_check_timed_out,
                 source: "where".to_string(),
                 installation_type: InstallationType::System,
+                node_version: None,
             })
         }
         _ => None,
     }
 }
 
+/// How long the login shell may take to resolve `claude`
+const SHELL_LOOKUP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Warning raised when the shell's `claude` is not the installation opcode selected
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClaudePathConflict {
+    /// Binary the user's shell resolves for `claude`
+    pub shell_path: String,
+    pub shell_version: Option<String>,
+    /// Binary opcode runs
+    pub selected_path: String,
+    pub selected_version: Option<String>,
+}
+
+/// Resolve `claude` the way the user's login shell would
+///
+/// GUI apps don't inherit the shell's PATH, so the app's own `which` can disagree
+/// with the terminal. Falls back to `which` when the shell lookup fails.
+#[cfg(unix)]
+fn shell_claude_path() -> Option<String> {
+    let shell = std::env::var("SHELL").unwrap_or_else(|_| "/bin/sh".to_string());
+    let child = Command::new(&shell)
+        .args(["-lc", "command -v claude"])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn();
+
+    let resolved = child.ok().and_then(|mut child| {
+        let deadline = Instant::now() + SHELL_LOOKUP_TIMEOUT;
+        loop {
+            match child.try_wait() {
+                Ok(Some(_)) => break,
+                Ok(None) if Instant::now() < deadline => {
+                    std::thread::sleep(Duration::from_millis(25))
+                }
+                _ => {
+                    warn!("Login shell lookup of claude via {} timed out", shell);
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return None;
+                }
+            }
+        }
+        let output = child.wait_with_output().ok()?;
+        let path = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .last()?
+            .trim()
+            .to_string();
+        // `command -v` prints the bare name for aliases and functions
+        (output.status.success() && path.starts_with('/')).then_some(path)
+    });
+
+    resolved.or_else(|| try_which_command().map(|installation| installation.path))
+}
+
+#[cfg(windows)]
+fn shell_claude_path() -> Option<String> {
+    try_which_command().map(|installation| installation.path)
+}
+
+/// Whether two paths point at the same binary once symlinks are resolved
+fn same_binary(a: &str, b: &str) -> bool {
+    let resolve = |path: &str| {
+        let path = if path.contains(std::path::MAIN_SEPARATOR) {
+            PathBuf::from(path)
+        } else {
+            which::which(path).unwrap_or_else(|_| PathBuf::from(path))
+        };
+        path.canonicalize().unwrap_or(path)
+    };
+    a == b || resolve(a) == resolve(b)
+}
+
+/// Compare the shell's `claude` against `selected_path`
+///
+/// Returns `None` when they are the same binary or the shell has no `claude`.
+pub fn detect_path_conflict(selected_path: &str) -> Option<ClaudePathConflict> {
+    let shell_path = shell_claude_path()?;
+    if same_binary(&shell_path, selected_path) {
+        return None;
+    }
+
+    warn!(
+        "Shell resolves claude to {} but opcode uses {}",
+        shell_path, selected_path
+    );
+    Some(ClaudePathConflict {
+        shell_version: get_claude_version(&shell_path).ok().flatten(),
+        selected_version: get_claude_version(selected_path).ok().flatten(),
+        shell_path,
+        selected_path: selected_path.to_string(),
+    })
+}
+
+/// Shareable summary of a Claude installation for support requests
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClaudeFingerprint {
+    /// Short hash over every field below; equal setups produce the same value
+    pub fingerprint: String,
+    /// SHA-256 of the binary path, so reports can be matched without revealing it
+    pub path_hash: String,
+    pub version: Option<String>,
+    /// "node" for script installs run by Node.js, "native" for standalone binaries
+    pub command_type: String,
+    pub node_version: Option<String>,
+    pub source: String,
+    pub installation_type: InstallationType,
+    pub os: String,
+    pub arch: String,
+}
+
+fn sha256_hex(value: &str) -> String {
+    use sha2::{Digest, Sha256};
+
+    let mut hasher = Sha256::new();
+    hasher.update(value.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// Build the fingerprint of an installation on the current platform
+pub fn fingerprint_installation(installation: &ClaudeInstallation) -> ClaudeFingerprint {
+    let command_type = if installation.node_version.is_some() || is_node_script(&installation.path)
+    {
+        "node"
+    } else {
+        "native"
+    };
+
+    let mut fingerprint = ClaudeFingerprint {
+        fingerprint: String::new(),
+        path_hash: sha256_hex(&installation.path),
+        version: installation.version.clone(),
+        command_type: command_type.to_string(),
+        node_version: installation.node_version.clone(),
+        source: installation.source.clone(),
+        installation_type: installation.installation_type.clone(),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+    };
+    let summary = [
+        fingerprint.path_hash.as_str(),
+        fingerprint.version.as_deref().unwrap_or(""),
+        fingerprint.command_type.as_str(),
+        fingerprint.node_version.as_deref().unwrap_or(""),
+        fingerprint.source.as_str(),
+        match fingerprint.installation_type {
+            InstallationType::System => "system",
+            InstallationType::Custom => "custom",
+        },
+        fingerprint.os.as_str(),
+        fingerprint.arch.as_str(),
+    ]
+    .join("|");
+    fingerprint.fingerprint = sha256_hex(&summary)[..16].to_string();
+    fingerprint
+}
+
+/// Fingerprint the installation at `selected_path`
+///
+/// Discovery metadata is used when the binary is among the discovered
+/// installations; otherwise it is described as a custom install.
+pub fn claude_fingerprint(selected_path: &str, extra_binary_names: &[String]) -> ClaudeFingerprint {
+    let installation = discover_claude_installations(extra_binary_names)
+        .into_iter()
+        .find(|installation| same_binary(&installation.path, selected_path))
+        .unwrap_or_else(|| {
+            let (version, version_check_timed_out) = probe_claude_version(selected_path);
+            let mut custom = [ClaudeInstallation {
+                path: selected_path.to_string(),
+                version,
+                version_check_timed_out,
+                source: "custom".to_string(),
+                installation_type: InstallationType::Custom,
+                node_version: None,
+            }];
+            populate_node_versions(&mut custom);
+            let [installation] = custom;
+            installation
+        });
+    fingerprint_installation(&installation)
+}
+
 /// Find Claude installations in NVM directories
 #[cfg(unix)]
 fn find_nvm_installations() -> Vec<ClaudeInstallation> {
@@ -261,14 +708,15 @@ fn find_nvm_installations() -> Vec<ClaudeInstallation> {
         let claude_path = PathBuf::from(&nvm_bin).join("claude");
         if claude_path.exists() && claude_path.is_file() {
             debug!("Found Claude via NVM_BIN: {:?}", claude_path);
-            let version = get_claude_version(&claude_path.to_string_lossy())
-                .ok()
-                .flatten();
+            let (version, version_check_timed_out) =
+                probe_claude_version(&claude_path.to_string_lossy());
             installations.push(ClaudeInstallation {
                 path: claude_path.to_string_lossy().to_string(),
                 version,
+                version_check_timed_out,
                 source: "nvm-active".to_string(),
                 installation_type: InstallationType::System,
+                node_version: None,
             });
         }
     }
@@ -294,13 +742,15 @@ fn find_nvm_installations() -> Vec<ClaudeInstallation> {
                         debug!("Found Claude in NVM node {}: {}", node_version, path_str);
 
                         // Get Claude version
-                        let version = get_claude_version(&path_str).ok().flatten();
+                        let (version, version_check_timed_out) = probe_claude_version(&path_str);
 
                         installations.push(ClaudeInstallation {
                             path: path_str,
                             version,
+                            version_check_timed_out,
                             source: format!("nvm ({})", node_version),
                             installation_type: InstallationType::System,
+                            node_version: None,
                         });
                     }
                 }
@@ -330,13 +780,15 @@ fn find_nvm_installations() -> Vec<ClaudeInstallation> {
                         debug!("Found Claude in NVM node {}: {}", node_version, path_str);
 
                         // Get Claude version
-                        let version = get_claude_version(&path_str).ok().flatten();
+                        let (version, version_check_timed_out) = probe_claude_version(&path_str);
 
                         installations.push(ClaudeInstallation {
                             path: path_str,
                             version,
+                            version_check_timed_out,
                             source: format!("nvm ({})", node_version),
                             installation_type: InstallationType::System,
+                            node_version: None,
                         });
                     }
                 }
@@ -400,13 +852,15 @@ fn find_standard_installations() -> Vec<ClaudeInstallation> {
             debug!("Found claude at standard path: {} ({})", path, source);
 
             // Get version
-            let version = get_claude_version(&path).ok().flatten();
+            let (version, version_check_timed_out) = probe_claude_version(&path);
 
             installations.push(ClaudeInstallation {
                 path,
                 version,
+                version_check_timed_out,
                 source,
                 installation_type: InstallationType::System,
+                node_version: None,
             });
         }
     }
@@ -420,8 +874,10 @@ fn find_standard_installations() -> Vec<ClaudeInstallation> {
             installations.push(ClaudeInstallation {
                 path: "claude".to_string(),
                 version,
+                version_check_timed_out: false,
                 source: "PATH".to_string(),
                 installation_type: InstallationType::System,
+                node_version: None,
             });
         }
     }
@@ -469,13 +925,15 @@ fn find_standard_installations() -> Vec<ClaudeInstallation> {
             debug!("Found claude at standard path: {} ({})", path, source);
 
             // Get version
-            let version = get_claude_version(&path).ok().flatten();
+            let (version, version_check_timed_out) = probe_claude_version(&path);
 
             installations.push(ClaudeInstallation {
                 path,
                 version,
+                version_check_timed_out,
                 source,
                 installation_type: InstallationType::System,
+                node_version: None,
             });
         }
     }
@@ -489,8 +947,10 @@ fn find_standard_installations() -> Vec<ClaudeInstallation> {
             installations.push(ClaudeInstallation {
                 path: "claude.exe".to_string(),
                 version,
+                version_check_timed_out: false,
                 source: "PATH".to_string(),
                 installation_type: InstallationType::System,
+                node_version: None,
             });
         }
     }
@@ -498,16 +958,56 @@ fn find_standard_installations() -> Vec<ClaudeInstallation> {
     installations
 }
 
+/// How long a `--version` probe may run before the binary is given up on
+const VERSION_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// Get Claude version by running --version command
+///
+/// Returns `Err` when the binary doesn't answer within `VERSION_CHECK_TIMEOUT`,
+/// which callers should treat as "check timed out" rather than "not installed".
 fn get_claude_version(path: &str) -> Result<Option<String>, String> {
-    match Command::new(path).arg("--version").output() {
-        Ok(output) => {
-            if output.status.success() {
-                Ok(extract_version_from_output(&output.stdout))
-            } else {
-                Ok(None)
+    get_claude_version_with_timeout(path, VERSION_CHECK_TIMEOUT)
+}
+
+fn get_claude_version_with_timeout(
+    path: &str,
+    timeout: Duration,
+) -> Result<Option<String>, String> {
+    let mut child = match Command::new(path)
+        .arg("--version")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(e) => {
+            warn!("Failed to get version for {}: {}", path, e);
+            return Ok(None);
+        }
+    };
+
+    let deadline = Instant::now() + timeout;
+    loop {
+        match child.try_wait() {
+            Ok(Some(_)) => break,
+            Ok(None) if Instant::now() >= deadline => {
+                let _ = child.kill();
+                let _ = child.wait();
+                warn!("Version check for {} timed out after {:?}", path, timeout);
+                return Err(format!("Version check timed out after {:?}", timeout));
+            }
+            Ok(None) => std::thread::sleep(Duration::from_millis(25)),
+            Err(e) => {
+                warn!("Failed to wait for {}: {}", path, e);
+                return Ok(None);
             }
         }
+    }
+
+    match child.wait_with_output() {
+        Ok(output) if output.status.success() => Ok(extract_version_from_output(&output.stdout)),
+        Ok(_) => Ok(None),
         Err(e) => {
             warn!("Failed to get version for {}: {}", path, e);
             Ok(None)
@@ -515,6 +1015,14 @@ fn get_claude_version(path: &str) -> Result<Option<String>, String> {
     }
 }
 
+/// Probe `path` for its version, reporting whether the probe timed out
+fn probe_claude_version(path: &str) -> (Option<String>, bool) {
+    match get_claude_version(path) {
+        Ok(version) => (version, false),
+        Err(_) => (None, true),
+    }
+}
+
 /// Extract version string from command output
 fn extract_version_from_output(stdout: &[u8]) -> Option<String> {
     let output_str = String::from_utf8_lossy(stdout);
@@ -546,7 +1054,13 @@ fn extract_version_from_output(stdout: &[u8]) -> Option<String> {
 }
 
 /// Select the best installation based on version
-fn select_best_installation(installations: Vec<ClaudeInstallation>) -> Option<ClaudeInstallation> {
+///
+/// With `prefer_stable` set, installations whose version has a prerelease
+/// suffix rank below every stable one and are only picked as a last resort.
+fn select_best_installation(
+    installations: Vec<ClaudeInstallation>,
+    prefer_stable: bool,
+) -> Option<ClaudeInstallation> {
     // In production builds, version information may not be retrievable because
     // spawning external processes can be restricted. We therefore no longer
     // discard installations that lack a detected version – the mere presence
@@ -555,6 +1069,14 @@ fn select_best_installation(installations: Vec<ClaudeInstallation>) -> Option<Cl
     // in development builds we keep the previous behaviour of picking the
     // most recent version.
     installations.into_iter().max_by(|a, b| {
+        if prefer_stable {
+            match (is_prerelease(a), is_prerelease(b)) {
+                (false, true) => return Ordering::Greater,
+                (true, false) => return Ordering::Less,
+                _ => {}
+            }
+        }
+
         match (&a.version, &b.version) {
             // If both have versions, compare them semantically.
             (Some(v1), Some(v2)) => compare_versions(v1, v2),
@@ -577,10 +1099,19 @@ fn select_best_installation(installations: Vec<ClaudeInstallation>) -> Option<Cl
     })
 }
 
-/// Compare two version strings
-fn compare_versions(a: &str, b: &str) -> Ordering {
-    // Simple semantic version comparison
-    let a_parts: Vec<u32> = a
+/// Whether the installation's version carries a prerelease suffix such as "-beta"
+fn is_prerelease(installation: &ClaudeInstallation) -> bool {
+    installation
+        .version
+        .as_deref()
+        .and_then(parse_version_parts)
+        .and_then(|parts| parts.prerelease)
+        .is_some_and(|pre| !pre.is_empty())
+}
+
+/// Numeric components of a version string, e.g. "1.0.17-beta" -> [1, 0, 17]
+fn numeric_version_parts(version: &str) -> Vec<u32> {
+    version
         .split('.')
         .filter_map(|s| {
             // Handle versions like "1.0.17-beta" by taking only numeric part
@@ -590,18 +1121,14 @@ fn compare_versions(a: &str, b: &str) -> Ordering {
                 .parse()
                 .ok()
         })
-        .collect();
+        .collect()
+}
 
-    let b_parts: Vec<u32> = b
-        .split('.')
-        .filter_map(|s| {
-            s.chars()
-                .take_while(|c| c.is_numeric())
-                .collect::<String>()
-                .parse()
-                .ok()
-        })
-        .collect();
+/// Compare two version strings
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    // Simple semantic version comparison
+    let a_parts = numeric_version_parts(a);
+    let b_parts = numeric_version_parts(b);
 
     // Compare each part
     for i in 0..std::cmp::max(a_parts.len(), b_parts.len()) {
@@ -616,6 +1143,38 @@ fn compare_versions(a: &str, b: &str) -> Ordering {
     Ordering::Equal
 }
 
+/// Structured components of a Claude version
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ClaudeVersionParts {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+    pub prerelease: Option<String>,
+}
+
+/// Split a version string such as "1.0.41-beta.1+build" into its components
+pub fn parse_version_parts(version: &str) -> Option<ClaudeVersionParts> {
+    let version = version.trim();
+    let without_build = version.split('+').next().unwrap_or(version);
+    let (core, prerelease) = match without_build.split_once('-') {
+        Some((core, pre)) => (core, Some(pre.to_string())),
+        None => (without_build, None),
+    };
+
+    let parts = numeric_version_parts(core);
+    Some(ClaudeVersionParts {
+        major: *parts.first()?,
+        minor: parts.get(1).copied().unwrap_or(0),
+        patch: parts.get(2).copied().unwrap_or(0),
+        prerelease,
+    })
+}
+
+/// Detect the version of the Claude binary at `path`, if it can be determined
+pub fn detect_claude_version(path: &str) -> Option<String> {
+    get_claude_version(path).ok().flatten()
+}
+
 /// Helper function to create a Command with proper environment variables
 /// This ensures commands like Claude can find Node.js and other dependencies
 pub fn create_command_with_env(program: &str) -> Command {
@@ -691,3 +1250,173 @@ pub fn create_command_with_env(program: &str) -> Command {
 
     cmd
 }
+
+/// Returns the environment variables `create_command_with_env` would set for `program`,
+/// without spawning anything
+pub fn effective_command_env(program: &str) -> std::collections::HashMap<String, String> {
+    create_command_with_env(program)
+        .get_envs()
+        .filter_map(|(key, value)| {
+            Some((
+                key.to_string_lossy().into_owned(),
+                value?.to_string_lossy().into_owned(),
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fingerprint_is_stable_and_hides_path() {
+        let mut nvm = installation("/home/u/.nvm/versions/node/v20.11.0/bin/claude", "1.0.5");
+        nvm.node_version = Some("20.11.0".to_string());
+
+        let first = fingerprint_installation(&nvm);
+        let second = fingerprint_installation(&nvm);
+        assert_eq!(first.fingerprint, second.fingerprint);
+        assert_eq!(first.fingerprint.len(), 16);
+        assert_eq!(first.command_type, "node");
+        assert!(!first.path_hash.contains("/home/u"));
+
+        nvm.version = Some("1.0.6".to_string());
+        assert_ne!(
+            fingerprint_installation(&nvm).fingerprint,
+            first.fingerprint
+        );
+    }
+
+    #[test]
+    fn test_node_version_from_path() {
+        assert_eq!(
+            node_version_from_path("/home/u/.nvm/versions/node/v20.11.0/bin/claude", "nvm"),
+            Some("20.11.0".to_string())
+        );
+        assert_eq!(
+            node_version_from_path(
+                "/home/u/.local/share/fnm/node-versions/v18.19.0/installation/bin/claude",
+                "which"
+            ),
+            Some("18.19.0".to_string())
+        );
+        assert_eq!(
+            node_version_from_path("/home/u/.volta/tools/image/node/22.1.0/bin/claude", "which"),
+            Some("22.1.0".to_string())
+        );
+        assert_eq!(
+            node_version_from_path(r"C:\nvm4w\v20.1.0\claude.exe", "nvm (v20.1.0)"),
+            Some("20.1.0".to_string())
+        );
+        assert_eq!(
+            node_version_from_path("/usr/local/bin/claude", "system"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_is_node_script() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let script = dir.path().join("cli.js");
+        let native = dir.path().join("claude");
+        std::fs::write(&script, "#!/usr/bin/env node\nconsole.log(1)\n").unwrap();
+        std::fs::write(&native, [0x7f, b'E', b'L', b'F', 0, 0]).unwrap();
+
+        assert!(is_node_script(script.to_str().unwrap()));
+        assert!(!is_node_script(native.to_str().unwrap()));
+        assert!(!is_node_script(
+            dir.path().join("missing").to_str().unwrap()
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_same_binary_follows_symlinks() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let real = dir.path().join("claude-real");
+        let link = dir.path().join("claude");
+        let other = dir.path().join("claude-other");
+        std::fs::write(&real, "").unwrap();
+        std::fs::write(&other, "").unwrap();
+        std::os::unix::fs::symlink(&real, &link).unwrap();
+
+        assert!(same_binary(link.to_str().unwrap(), real.to_str().unwrap()));
+        assert!(!same_binary(
+            other.to_str().unwrap(),
+            real.to_str().unwrap()
+        ));
+    }
+
+    fn installation(path: &str, version: &str) -> ClaudeInstallation {
+        ClaudeInstallation {
+            path: path.to_string(),
+            version: Some(version.to_string()),
+            version_check_timed_out: false,
+            source: "test".to_string(),
+            installation_type: InstallationType::System,
+            node_version: None,
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_version_check_times_out_on_slow_binary() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let binary = dir.path().join("slow-claude");
+        std::fs::write(&binary, "#!/bin/sh\nsleep 5\necho 1.0.0\n").unwrap();
+        std::fs::set_permissions(&binary, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let started = Instant::now();
+        let result =
+            get_claude_version_with_timeout(&binary.to_string_lossy(), Duration::from_millis(200));
+        assert!(result.unwrap_err().contains("timed out"));
+        assert!(started.elapsed() < Duration::from_secs(4));
+    }
+
+    #[test]
+    fn test_parse_extra_binary_names() {
+        assert_eq!(
+            parse_extra_binary_names(r#"["claude-wrapper", "cc", "cc"]"#),
+            vec!["claude-wrapper", "cc"]
+        );
+        assert_eq!(
+            parse_extra_binary_names("claude-work, ,claude-home"),
+            vec!["claude-work", "claude-home"]
+        );
+        assert!(parse_extra_binary_names(r#"["../evil", "/usr/bin/x"]"#).is_empty());
+    }
+
+    #[test]
+    fn test_select_best_installation_version_ordering() {
+        let installations = vec![
+            installation("/stable", "1.0.40"),
+            installation("/beta", "1.0.41-beta.1"),
+        ];
+        let best = select_best_installation(installations, false).unwrap();
+        assert_eq!(best.path, "/beta");
+    }
+
+    #[test]
+    fn test_select_best_installation_prefers_stable() {
+        let installations = vec![
+            installation("/beta", "1.0.41-beta.1"),
+            installation("/stable", "1.0.40"),
+            installation("/older", "1.0.30"),
+        ];
+        let best = select_best_installation(installations, true).unwrap();
+        assert_eq!(best.path, "/stable");
+    }
+
+    #[test]
+    fn test_select_best_installation_falls_back_to_prerelease() {
+        let installations = vec![
+            installation("/beta", "1.0.41-beta.1"),
+            installation("/alpha", "1.0.42-alpha"),
+        ];
+        let best = select_best_installation(installations, true).unwrap();
+        assert_eq!(best.path, "/alpha");
+    }
+}
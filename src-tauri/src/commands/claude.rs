@@ -2,7 +2,7 @@ use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::io::{BufRead, BufReader};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Stdio;
 use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -57,6 +57,25 @@ pub struct Session {
     pub message_timestamp: Option<String>,
 }
 
+/// Summary of an interactive Claude session discovered from its JSONL file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClaudeSessionSummary {
+    /// The session ID (JSONL file name without extension)
+    pub session_id: String,
+    /// The project ID (encoded directory name under ~/.claude/projects)
+    pub project_id: String,
+    /// The project path
+    pub project_path: String,
+    /// Timestamp of the first entry, if any entry has one
+    pub started_at: Option<String>,
+    /// Number of user and assistant messages
+    pub message_count: usize,
+    /// Timestamp of the last entry, falling back to the file modification time
+    pub last_activity: Option<String>,
+    /// Lines that could not be parsed (e.g. a partially written last line)
+    pub malformed_lines: usize,
+}
+
 /// Represents a message entry in the JSONL file
 #[derive(Debug, Deserialize)]
 struct JsonlEntry {
@@ -100,6 +119,118 @@ pub struct ClaudeVersionStatus {
     pub output: String,
 }
 
+/// Result of a one-shot test run of the Claude binary
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClaudeExecutionTest {
+    /// Whether Claude ran and exited successfully
+    pub success: bool,
+    /// The binary that was executed
+    pub claude_path: Option<String>,
+    /// Exit code, if the process exited on its own
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+    pub duration_ms: u64,
+    /// Launch failure or timeout description
+    pub error: Option<String>,
+}
+
+/// How long a test run of Claude may take before it is reported as hung
+const CLAUDE_TEST_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// A known Anthropic model offered in the model picker
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnthropicModel {
+    /// Full model ID as accepted by the API
+    pub id: String,
+    /// Short alias accepted by `claude --model`
+    pub alias: String,
+    pub display_name: String,
+    /// Context window in tokens
+    pub context_window: u32,
+}
+
+/// Built-in model list, independent of any third-party model catalog
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnthropicModelList {
+    /// Revision of the built-in table, bumped whenever it changes
+    pub version: u32,
+    pub models: Vec<AnthropicModel>,
+}
+
+/// Revision of `ANTHROPIC_MODELS`
+const ANTHROPIC_MODELS_VERSION: u32 = 1;
+
+/// Known Anthropic models as (id, alias, display name, context window)
+const ANTHROPIC_MODELS: &[(&str, &str, &str, u32)] = &[
+    ("claude-opus-4-20250514", "opus", "Claude Opus 4", 200_000),
+    (
+        "claude-sonnet-4-20250514",
+        "sonnet",
+        "Claude Sonnet 4",
+        200_000,
+    ),
+    (
+        "claude-3-5-haiku-20241022",
+        "haiku",
+        "Claude Haiku 3.5",
+        200_000,
+    ),
+];
+
+/// Outcome of checking whether the selected Claude accepts a model string
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelValidation {
+    pub model: String,
+    /// Whether the model was accepted; None when the check was inconclusive
+    pub recognized: Option<bool>,
+    /// Error reported by Claude, or why the check was inconclusive
+    pub message: Option<String>,
+    /// The check hit a temporary API error (overloaded, rate limited); retrying may succeed
+    #[serde(default)]
+    pub transient: bool,
+}
+
+/// How long a model pre-flight check may take
+const MODEL_VALIDATION_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Why a model pre-flight run failed
+#[derive(Debug, PartialEq)]
+enum ModelCheckFailure {
+    /// The API rejected the model string
+    UnknownModel,
+    /// A temporary API condition unrelated to the model
+    Transient,
+    /// Anything else (auth, network, CLI errors)
+    Other,
+}
+
+/// Classify Claude's output from a failed model pre-flight run
+fn classify_model_check_failure(output: &str) -> ModelCheckFailure {
+    let lower = output.to_lowercase();
+    const TRANSIENT: [&str; 5] = [
+        "overloaded",
+        "rate limit",
+        "rate_limit",
+        "too many requests",
+        "529",
+    ];
+    const UNKNOWN_MODEL: [&str; 5] = [
+        "not_found_error",
+        "model not found",
+        "invalid model",
+        "unknown model",
+        "issue with the selected model",
+    ];
+    if TRANSIENT.iter().any(|p| lower.contains(p)) {
+        ModelCheckFailure::Transient
+    } else if UNKNOWN_MODEL.iter().any(|p| lower.contains(p)) {
+        ModelCheckFailure::UnknownModel
+    } else {
+        ModelCheckFailure::Other
+    }
+}
+
 /// Represents a CLAUDE.md file found in the project
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClaudeMdFile {
@@ -229,6 +360,83 @@ fn extract_first_user_message(jsonl_path: &PathBuf) -> (Option<String>, Option<S
     (None, None)
 }
 
+/// Reads a session JSONL file, skipping malformed lines instead of failing
+fn summarize_session_file(jsonl_path: &Path) -> (Option<String>, usize, Option<String>, usize) {
+    let mut started_at = None;
+    let mut last_activity = None;
+    let mut message_count = 0;
+    let mut malformed_lines = 0;
+
+    if let Ok(file) = fs::File::open(jsonl_path) {
+        for line in BufReader::new(file).lines() {
+            let Ok(line) = line else {
+                malformed_lines += 1;
+                continue;
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+            let Ok(entry) = serde_json::from_str::<serde_json::Value>(&line) else {
+                malformed_lines += 1;
+                continue;
+            };
+
+            if matches!(
+                entry.get("type").and_then(|t| t.as_str()),
+                Some("user") | Some("assistant")
+            ) {
+                message_count += 1;
+            }
+            if let Some(timestamp) = entry.get("timestamp").and_then(|t| t.as_str()) {
+                if started_at.is_none() {
+                    started_at = Some(timestamp.to_string());
+                }
+                last_activity = Some(timestamp.to_string());
+            }
+        }
+    }
+
+    if last_activity.is_none() {
+        last_activity = fs::metadata(jsonl_path)
+            .and_then(|m| m.modified())
+            .ok()
+            .map(|modified| chrono::DateTime::<chrono::Utc>::from(modified).to_rfc3339());
+    }
+
+    (started_at, message_count, last_activity, malformed_lines)
+}
+
+/// Summarizes every session JSONL file in a single project directory
+fn list_sessions_in_project_dir(
+    project_dir: &Path,
+    project_id: &str,
+    project_path: &str,
+) -> Vec<ClaudeSessionSummary> {
+    let Ok(entries) = fs::read_dir(project_dir) else {
+        return Vec::new();
+    };
+
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("jsonl"))
+        .filter_map(|path| {
+            let session_id = path.file_stem()?.to_str()?.to_string();
+            let (started_at, message_count, last_activity, malformed_lines) =
+                summarize_session_file(&path);
+            Some(ClaudeSessionSummary {
+                session_id,
+                project_id: project_id.to_string(),
+                project_path: project_path.to_string(),
+                started_at,
+                message_count,
+                last_activity,
+                malformed_lines,
+            })
+        })
+        .collect()
+}
+
 /// Helper function to create a tokio Command with proper environment variables
 /// This ensures commands like Claude can find Node.js and other dependencies
 fn create_command_with_env(program: &str) -> Command {
@@ -313,6 +521,39 @@ pub async fn get_home_directory() -> Result<String, String> {
         .ok_or_else(|| "Could not determine home directory".to_string())
 }
 
+/// Walks up from `path` to the nearest directory containing `.git` or `.claude`
+///
+/// The home directory's `.claude` holds user-level configuration, so it does not
+/// mark a project root.
+pub fn find_project_root_dir(path: &Path) -> Option<PathBuf> {
+    let home_dir = dirs::home_dir();
+
+    path.ancestors()
+        .find(|dir| {
+            dir.join(".git").exists()
+                || (dir.join(".claude").is_dir() && home_dir.as_deref() != Some(*dir))
+        })
+        .map(Path::to_path_buf)
+}
+
+/// Resolves the directory to read project-scoped configuration from
+/// Only walks up to the project root when `walk_up` is enabled
+pub fn resolve_project_dir(project_path: &str, walk_up: bool) -> PathBuf {
+    let path = PathBuf::from(project_path);
+    if walk_up {
+        if let Some(root) = find_project_root_dir(&path) {
+            return root;
+        }
+    }
+    path
+}
+
+/// Finds the project root (nearest `.git` or `.claude` directory) for a path
+#[tauri::command]
+pub async fn find_project_root(path: String) -> Result<Option<String>, String> {
+    Ok(find_project_root_dir(Path::new(&path)).map(|root| root.to_string_lossy().to_string()))
+}
+
 /// Lists all projects in the ~/.claude/projects directory
 #[tauri::command]
 pub async fn list_projects() -> Result<Vec<Project>, String> {
@@ -421,6 +662,151 @@ pub async fn list_projects() -> Result<Vec<Project>, String> {
     Ok(projects)
 }
 
+/// Setting key for the project used when a command's `project_path` is omitted
+pub const CURRENT_PROJECT_KEY: &str = "current_project";
+
+/// Current project loaded from settings, so commands without state access can use it
+static CURRENT_PROJECT: std::sync::Mutex<Option<String>> = std::sync::Mutex::new(None);
+
+/// Returns `project_path`, or the current project when it is `None`
+///
+/// Only for commands where a missing project would otherwise be an error; where
+/// `None` means "user scope only" or "all projects", callers must keep that meaning.
+pub fn or_current_project(project_path: Option<String>) -> Option<String> {
+    project_path.or_else(|| {
+        CURRENT_PROJECT
+            .lock()
+            .ok()
+            .and_then(|current| current.clone())
+    })
+}
+
+/// Load the current project from settings, ignoring it if the directory is gone
+pub fn load_current_project(conn: &rusqlite::Connection) {
+    let current = super::agents::read_setting(conn, CURRENT_PROJECT_KEY)
+        .ok()
+        .flatten()
+        .filter(|path| !path.is_empty());
+
+    let current = match current {
+        Some(path) if Path::new(&path).is_dir() => {
+            log::info!("Current project: {}", path);
+            Some(path)
+        }
+        Some(path) => {
+            log::warn!("Ignoring current project that no longer exists: {}", path);
+            None
+        }
+        None => None,
+    };
+
+    if let Ok(mut slot) = CURRENT_PROJECT.lock() {
+        *slot = current;
+    }
+}
+
+/// Gets the current project path, if one is set
+///
+/// Returns the value commands actually fall back to, which omits a stored project
+/// whose directory no longer existed at startup.
+#[tauri::command]
+pub async fn get_current_project() -> Result<Option<String>, String> {
+    Ok(CURRENT_PROJECT.lock().map_err(|e| e.to_string())?.clone())
+}
+
+/// Sets (or with `None`, clears) the current project used when `project_path` is omitted
+///
+/// Setting a project runs the same cache invalidation as `on_project_changed`.
+#[tauri::command]
+pub async fn set_current_project(
+    app: AppHandle,
+    db: tauri::State<'_, super::agents::AgentDb>,
+    path: Option<String>,
+) -> Result<(), String> {
+    let path = path.map(|p| p.trim().to_string()).filter(|p| !p.is_empty());
+    if let Some(path) = &path {
+        if !Path::new(path).is_dir() {
+            return Err(format!("Project path is not a directory: {}", path));
+        }
+    }
+
+    {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        super::agents::write_setting(&conn, CURRENT_PROJECT_KEY, path.as_deref().unwrap_or(""))?;
+    }
+    if let Ok(mut slot) = CURRENT_PROJECT.lock() {
+        *slot = path.clone();
+    }
+
+    match path {
+        Some(path) => on_project_changed(app, path).await,
+        None => Ok(()),
+    }
+}
+
+/// Payload of the `project-changed` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectChangedEvent {
+    pub project_path: String,
+    /// Number of project-scoped slash commands found for the new project
+    pub slash_commands: usize,
+    /// Number of servers in the new project's .mcp.json
+    pub mcp_servers: usize,
+}
+
+/// Clears project-sensitive state when the user switches projects, re-reads the
+/// new project's commands and MCP config, and emits `project-changed` when done
+#[tauri::command]
+pub async fn on_project_changed(app: AppHandle, project_path: String) -> Result<(), String> {
+    log::info!("Project changed to: {}", project_path);
+
+    if !Path::new(&project_path).is_dir() {
+        return Err(format!("Project path is not a directory: {}", project_path));
+    }
+
+    // MCP server health includes project-scoped servers
+    if let Some(cache) = app.try_state::<super::mcp::MCPHealthCache>() {
+        cache.clear();
+    }
+
+    let slash_commands = match super::slash_commands::slash_commands_list(
+        Some(project_path.clone()),
+        None,
+        None,
+        None,
+    )
+    .await
+    {
+        Ok(commands) => commands
+            .iter()
+            .filter(|command| command.scope == "project")
+            .count(),
+        Err(e) => {
+            log::warn!("Failed to load slash commands for new project: {}", e);
+            0
+        }
+    };
+
+    let mcp_servers = match super::mcp::mcp_read_project_config(project_path.clone(), None).await {
+        Ok(config) => config.mcp_servers.len(),
+        Err(e) => {
+            log::warn!("Failed to read .mcp.json for new project: {}", e);
+            0
+        }
+    };
+
+    let _ = app.emit(
+        "project-changed",
+        ProjectChangedEvent {
+            project_path,
+            slash_commands,
+            mcp_servers,
+        },
+    );
+
+    Ok(())
+}
+
 /// Creates a new project for the given directory path
 #[tauri::command]
 pub async fn create_project(path: String) -> Result<Project, String> {
@@ -555,6 +941,100 @@ pub async fn get_project_sessions(project_id: String) -> Result<Vec<Session>, St
     Ok(sessions)
 }
 
+/// Lists interactive Claude sessions found in ~/.claude/projects, newest activity first
+///
+/// With `project_path`, only sessions recorded for that project are returned.
+#[tauri::command]
+pub async fn list_claude_sessions(
+    project_path: Option<String>,
+) -> Result<Vec<ClaudeSessionSummary>, String> {
+    log::info!("Listing Claude sessions (project: {:?})", project_path);
+
+    let claude_dir = get_claude_dir().map_err(|e| e.to_string())?;
+    let projects_dir = claude_dir.join("projects");
+    if !projects_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let wanted_id = project_path.as_ref().map(|path| path.replace('/', "-"));
+
+    tokio::task::spawn_blocking(move || {
+        let entries = fs::read_dir(&projects_dir)
+            .map_err(|e| format!("Failed to read projects directory: {}", e))?;
+
+        let mut sessions = Vec::new();
+        for entry in entries.flatten() {
+            let dir = entry.path();
+            if !dir.is_dir() {
+                continue;
+            }
+            let Some(project_id) = dir.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+
+            let resolved_path = get_project_path_from_sessions(&dir)
+                .unwrap_or_else(|_| decode_project_path(project_id));
+            if let (Some(wanted_path), Some(wanted_id)) = (&project_path, &wanted_id) {
+                if project_id != wanted_id && &resolved_path != wanted_path {
+                    continue;
+                }
+            }
+
+            sessions.extend(list_sessions_in_project_dir(
+                &dir,
+                project_id,
+                &resolved_path,
+            ));
+        }
+
+        sessions.sort_by(|a, b| b.last_activity.cmp(&a.last_activity));
+        log::info!("Found {} Claude sessions", sessions.len());
+        Ok(sessions)
+    })
+    .await
+    .map_err(|e| format!("Failed to list sessions: {}", e))?
+}
+
+/// Resolves the session log directory for `project_path`, or the current project
+fn resolve_session_log_dir(project_path: Option<String>) -> Result<PathBuf, String> {
+    let project_path = or_current_project(project_path)
+        .ok_or("No project path given and no current project is set")?;
+    super::agents::session_log_dir(&project_path)
+}
+
+/// Returns the directory Claude writes session JSONL logs to for a project
+///
+/// Falls back to the current project when `project_path` is omitted. The directory
+/// may not exist yet if Claude has never run in the project.
+#[tauri::command]
+pub async fn get_claude_session_log_dir(project_path: Option<String>) -> Result<String, String> {
+    let dir = resolve_session_log_dir(project_path)?;
+    Ok(dir.to_string_lossy().to_string())
+}
+
+/// Opens a project's Claude session log directory in the system file manager
+#[tauri::command]
+pub async fn open_claude_session_logs(project_path: Option<String>) -> Result<String, String> {
+    let dir = resolve_session_log_dir(project_path)?;
+    if !dir.is_dir() {
+        return Err(format!(
+            "No session logs found for this project yet: {}",
+            dir.display()
+        ));
+    }
+
+    let spawn_result = if cfg!(target_os = "macos") {
+        std::process::Command::new("open").arg(&dir).spawn()
+    } else if cfg!(target_os = "windows") {
+        std::process::Command::new("explorer").arg(&dir).spawn()
+    } else {
+        std::process::Command::new("xdg-open").arg(&dir).spawn()
+    };
+    spawn_result.map_err(|e| format!("Failed to open file manager: {}", e))?;
+
+    Ok(dir.to_string_lossy().to_string())
+}
+
 /// Reads the Claude settings file
 #[tauri::command]
 pub async fn get_claude_settings() -> Result<ClaudeSettings, String> {
@@ -637,6 +1117,25 @@ pub async fn get_system_prompt() -> Result<String, String> {
     fs::read_to_string(&claude_md_path).map_err(|e| format!("Failed to read CLAUDE.md: {}", e))
 }
 
+/// Lists the built-in Anthropic models so a model can be picked without any API key
+#[tauri::command]
+pub async fn get_anthropic_models() -> Result<AnthropicModelList, String> {
+    let models = ANTHROPIC_MODELS
+        .iter()
+        .map(|(id, alias, display_name, context_window)| AnthropicModel {
+            id: id.to_string(),
+            alias: alias.to_string(),
+            display_name: display_name.to_string(),
+            context_window: *context_window,
+        })
+        .collect();
+
+    Ok(AnthropicModelList {
+        version: ANTHROPIC_MODELS_VERSION,
+        models,
+    })
+}
+
 /// Checks if Claude Code is installed and gets its version
 #[tauri::command]
 pub async fn check_claude_version(app: AppHandle) -> Result<ClaudeVersionStatus, String> {
@@ -678,9 +1177,13 @@ pub async fn check_claude_version(app: AppHandle) -> Result<ClaudeVersionStatus,
 
     #[cfg(debug_assertions)]
     {
-        let output = std::process::Command::new(claude_path)
-            .arg("--version")
-            .output();
+        let output = tokio::task::spawn_blocking(move || {
+            std::process::Command::new(claude_path)
+                .arg("--version")
+                .output()
+        })
+        .await
+        .map_err(|e| format!("Version check task failed: {}", e))?;
 
         match output {
             Ok(output) => {
@@ -729,6 +1232,148 @@ pub async fn check_claude_version(app: AppHandle) -> Result<ClaudeVersionStatus,
     }
 }
 
+/// Runs the selected Claude binary once with a trivial prompt, using the same
+/// environment as real sessions, and reports its output
+#[tauri::command]
+pub async fn test_claude_execution(
+    app: AppHandle,
+    prompt: String,
+) -> Result<ClaudeExecutionTest, String> {
+    log::info!("Test-running Claude Code");
+
+    let started = std::time::Instant::now();
+    let mut result = ClaudeExecutionTest {
+        success: false,
+        claude_path: None,
+        exit_code: None,
+        stdout: String::new(),
+        stderr: String::new(),
+        duration_ms: 0,
+        error: None,
+    };
+
+    let claude_path = match find_claude_binary(&app) {
+        Ok(path) => path,
+        Err(e) => {
+            result.error = Some(e);
+            return Ok(result);
+        }
+    };
+    result.claude_path = Some(claude_path.clone());
+
+    let prompt = if prompt.trim().is_empty() {
+        "Reply with the single word OK.".to_string()
+    } else {
+        prompt
+    };
+
+    let mut cmd = create_command_with_env(&claude_path);
+    cmd.args(["-p", &prompt, "--output-format", "text"])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+
+    match tokio::time::timeout(CLAUDE_TEST_TIMEOUT, cmd.output()).await {
+        Ok(Ok(output)) => {
+            result.success = output.status.success();
+            result.exit_code = output.status.code();
+            result.stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            result.stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        }
+        Ok(Err(e)) => {
+            log::error!("Failed to run claude command: {}", e);
+            result.error = Some(format!("Failed to launch Claude: {}", e));
+        }
+        Err(_) => {
+            log::warn!(
+                "Claude test run timed out after {}s",
+                CLAUDE_TEST_TIMEOUT.as_secs()
+            );
+            result.error = Some(format!(
+                "Claude did not finish within {} seconds",
+                CLAUDE_TEST_TIMEOUT.as_secs()
+            ));
+        }
+    }
+
+    result.duration_ms = started.elapsed().as_millis() as u64;
+    Ok(result)
+}
+
+/// Pre-flight check that the selected Claude accepts a model string
+///
+/// Runs a minimal single-turn prompt with `--model`, bounded by
+/// `MODEL_VALIDATION_TIMEOUT`, so bad model strings are caught before a full run.
+#[tauri::command]
+pub async fn validate_model_for_claude(
+    app: AppHandle,
+    model: String,
+) -> Result<ModelValidation, String> {
+    let model = model.trim().to_string();
+    log::info!("Validating model for Claude: {}", model);
+
+    let mut validation = ModelValidation {
+        model: model.clone(),
+        recognized: None,
+        message: None,
+        transient: false,
+    };
+
+    if model.is_empty() {
+        validation.recognized = Some(false);
+        validation.message = Some("Model must not be empty".to_string());
+        return Ok(validation);
+    }
+
+    let claude_path = find_claude_binary(&app)?;
+    let mut cmd = create_command_with_env(&claude_path);
+    cmd.args([
+        "-p",
+        "Reply with the single word OK.",
+        "--model",
+        &model,
+        "--max-turns",
+        "1",
+        "--output-format",
+        "text",
+    ])
+    .stdin(Stdio::null())
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .kill_on_drop(true);
+
+    match tokio::time::timeout(MODEL_VALIDATION_TIMEOUT, cmd.output()).await {
+        Ok(Ok(output)) if output.status.success() => {
+            validation.recognized = Some(true);
+        }
+        Ok(Ok(output)) => {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let combined = format!("{}\n{}", stdout.trim(), stderr.trim());
+            // Only a model-specific error proves the model is unknown; other
+            // failures (auth, network, overload) leave the question open
+            match classify_model_check_failure(&combined) {
+                ModelCheckFailure::UnknownModel => validation.recognized = Some(false),
+                ModelCheckFailure::Transient => validation.transient = true,
+                ModelCheckFailure::Other => {}
+            }
+            validation.message = Some(combined.trim().to_string());
+        }
+        Ok(Err(e)) => {
+            validation.message = Some(format!("Failed to launch Claude: {}", e));
+        }
+        Err(_) => {
+            validation.message = Some(format!(
+                "Claude did not respond within {} seconds",
+                MODEL_VALIDATION_TIMEOUT.as_secs()
+            ));
+        }
+    }
+
+    Ok(validation)
+}
+
 /// Saves the CLAUDE.md system prompt file
 #[tauri::command]
 pub async fn save_system_prompt(content: String) -> Result<String, String> {
@@ -916,14 +1561,35 @@ pub async fn load_session_history(
     Ok(messages)
 }
 
+/// Resolve the model and tool flags for a prompt that may invoke a slash command
+///
+/// Model precedence: an explicit `model` from the UI, then the invoked command's
+/// frontmatter `model`, then "sonnet". The command's tool restrictions are
+/// returned as extra CLI flags.
+async fn resolve_prompt_command(
+    prompt: &str,
+    project_path: &str,
+    model: Option<String>,
+) -> (String, Vec<String>) {
+    let command =
+        super::slash_commands::find_invoked_command(prompt, Some(project_path.to_string())).await;
+    let model = super::slash_commands::resolve_command_model(command.as_ref(), model, "sonnet");
+    let tool_args = command
+        .as_ref()
+        .map(super::slash_commands::command_tool_args)
+        .unwrap_or_default();
+    (model, tool_args)
+}
+
 /// Execute a new interactive Claude Code session with streaming output
 #[tauri::command]
 pub async fn execute_claude_code(
     app: AppHandle,
     project_path: String,
     prompt: String,
-    model: String,
+    model: Option<String>,
 ) -> Result<(), String> {
+    let (model, tool_args) = resolve_prompt_command(&prompt, &project_path, model).await;
     log::info!(
         "Starting new Claude Code session in: {} with model: {}",
         project_path,
@@ -932,7 +1598,7 @@ pub async fn execute_claude_code(
 
     let claude_path = find_claude_binary(&app)?;
 
-    let args = vec![
+    let mut args = vec![
         "-p".to_string(),
         prompt.clone(),
         "--model".to_string(),
@@ -942,6 +1608,7 @@ pub async fn execute_claude_code(
         "--verbose".to_string(),
         "--dangerously-skip-permissions".to_string(),
     ];
+    args.extend(tool_args);
 
     let cmd = create_system_command(&claude_path, args, &project_path);
     spawn_claude_process(app, cmd, prompt, model, project_path).await
@@ -953,8 +1620,9 @@ pub async fn continue_claude_code(
     app: AppHandle,
     project_path: String,
     prompt: String,
-    model: String,
+    model: Option<String>,
 ) -> Result<(), String> {
+    let (model, tool_args) = resolve_prompt_command(&prompt, &project_path, model).await;
     log::info!(
         "Continuing Claude Code conversation in: {} with model: {}",
         project_path,
@@ -963,7 +1631,7 @@ pub async fn continue_claude_code(
 
     let claude_path = find_claude_binary(&app)?;
 
-    let args = vec![
+    let mut args = vec![
         "-c".to_string(), // Continue flag
         "-p".to_string(),
         prompt.clone(),
@@ -974,6 +1642,7 @@ pub async fn continue_claude_code(
         "--verbose".to_string(),
         "--dangerously-skip-permissions".to_string(),
     ];
+    args.extend(tool_args);
 
     let cmd = create_system_command(&claude_path, args, &project_path);
     spawn_claude_process(app, cmd, prompt, model, project_path).await
@@ -986,8 +1655,9 @@ pub async fn resume_claude_code(
     project_path: String,
     session_id: String,
     prompt: String,
-    model: String,
+    model: Option<String>,
 ) -> Result<(), String> {
+    let (model, tool_args) = resolve_prompt_command(&prompt, &project_path, model).await;
     log::info!(
         "Resuming Claude Code session: {} in: {} with model: {}",
         session_id,
@@ -997,7 +1667,7 @@ pub async fn resume_claude_code(
 
     let claude_path = find_claude_binary(&app)?;
 
-    let args = vec![
+    let mut args = vec![
         "--resume".to_string(),
         session_id.clone(),
         "-p".to_string(),
@@ -1009,6 +1679,7 @@ pub async fn resume_claude_code(
         "--verbose".to_string(),
         "--dangerously-skip-permissions".to_string(),
     ];
+    args.extend(tool_args);
 
     let cmd = create_system_command(&claude_path, args, &project_path);
     spawn_claude_process(app, cmd, prompt, model, project_path).await
@@ -1091,15 +1762,19 @@ pub async fn cancel_claude_execution(
                     // Method 3: If we have a PID, try system kill as last resort
                     if let Some(pid) = pid {
                         log::info!("Attempting system kill as last resort for PID: {}", pid);
-                        let kill_result = if cfg!(target_os = "windows") {
-                            std::process::Command::new("taskkill")
-                                .args(["/F", "/PID", &pid.to_string()])
-                                .output()
-                        } else {
-                            std::process::Command::new("kill")
-                                .args(["-KILL", &pid.to_string()])
-                                .output()
-                        };
+                        let kill_result = tokio::task::spawn_blocking(move || {
+                            if cfg!(target_os = "windows") {
+                                std::process::Command::new("taskkill")
+                                    .args(["/F", "/PID", &pid.to_string()])
+                                    .output()
+                            } else {
+                                std::process::Command::new("kill")
+                                    .args(["-KILL", &pid.to_string()])
+                                    .output()
+                            }
+                        })
+                        .await
+                        .map_err(|e| format!("Kill task failed: {}", e))?;
 
                         match kill_result {
                             Ok(output) if output.status.success() => {
@@ -1186,6 +1861,14 @@ async fn spawn_claude_process(
         .spawn()
         .map_err(|e| format!("Failed to spawn Claude: {}", e))?;
 
+    // Slash command lookup scans the commands directories, so keep it off the hot path
+    {
+        let (prompt, model, project_path) = (prompt.clone(), model.clone(), project_path.clone());
+        tokio::spawn(async move {
+            super::slash_commands::record_prompt_execution(&prompt, &project_path, &model).await;
+        });
+    }
+
     // Get stdout and stderr
     let stdout = child.stdout.take().ok_or("Failed to get stdout")?;
     let stderr = child.stderr.take().ok_or("Failed to get stderr")?;
@@ -1664,6 +2347,26 @@ pub async fn list_checkpoints(
     Ok(manager.list_checkpoints().await)
 }
 
+/// Lists checkpoints for a session along with their file counts and disk usage
+#[tauri::command]
+pub async fn list_checkpoints_with_stats(
+    session_id: String,
+    project_id: String,
+) -> Result<Vec<crate::checkpoint::CheckpointStats>, String> {
+    use crate::checkpoint::storage::CheckpointStorage;
+
+    log::info!(
+        "Listing checkpoint stats for session: {} in project: {}",
+        session_id,
+        project_id
+    );
+
+    let claude_dir = get_claude_dir().map_err(|e| e.to_string())?;
+    CheckpointStorage::new(claude_dir)
+        .list_checkpoints_with_stats(&project_id, &session_id)
+        .map_err(|e| format!("Failed to list checkpoint stats: {}", e))
+}
+
 /// Forks a new timeline branch from a checkpoint
 #[tauri::command]
 pub async fn fork_from_checkpoint(
@@ -1774,6 +2477,50 @@ pub async fn update_checkpoint_settings(
         .map_err(|e| format!("Failed to update settings: {}", e))
 }
 
+/// Exports a checkpoint to a single archive file so it can be moved to another machine
+#[tauri::command]
+pub async fn export_checkpoint(
+    checkpoint_id: String,
+    session_id: String,
+    project_id: String,
+    dest_path: String,
+) -> Result<(), String> {
+    use crate::checkpoint::storage::CheckpointStorage;
+
+    log::info!("Exporting checkpoint {} to {}", checkpoint_id, dest_path);
+
+    let claude_dir = get_claude_dir().map_err(|e| e.to_string())?;
+    CheckpointStorage::new(claude_dir)
+        .export_checkpoint(
+            &project_id,
+            &session_id,
+            &checkpoint_id,
+            Path::new(&dest_path),
+        )
+        .map_err(|e| format!("Failed to export checkpoint: {}", e))
+}
+
+/// Imports a checkpoint archive into a session, assigning it a fresh checkpoint ID
+#[tauri::command]
+pub async fn import_checkpoint(
+    archive_path: String,
+    session_id: String,
+    project_id: String,
+) -> Result<crate::checkpoint::CheckpointResult, String> {
+    use crate::checkpoint::storage::CheckpointStorage;
+
+    log::info!(
+        "Importing checkpoint archive {} into session {}",
+        archive_path,
+        session_id
+    );
+
+    let claude_dir = get_claude_dir().map_err(|e| e.to_string())?;
+    CheckpointStorage::new(claude_dir)
+        .import_checkpoint(Path::new(&archive_path), &project_id, &session_id)
+        .map_err(|e| format!("Failed to import checkpoint: {}", e))
+}
+
 /// Gets diff between two checkpoints
 #[tauri::command]
 pub async fn get_checkpoint_diff(
@@ -2169,7 +2916,11 @@ pub async fn validate_hook_command(command: String) -> Result<serde_json::Value,
         .arg("-c")
         .arg(&command);
 
-    match cmd.output() {
+    let output = tokio::task::spawn_blocking(move || cmd.output())
+        .await
+        .map_err(|e| format!("Hook validation task failed: {}", e))?;
+
+    match output {
         Ok(output) => {
             if output.status.success() {
                 Ok(serde_json::json!({
@@ -2194,6 +2945,59 @@ mod tests {
     use std::io::Write;
     use tempfile::TempDir;
 
+    #[test]
+    fn test_classify_model_check_failure() {
+        assert_eq!(
+            classify_model_check_failure(
+                r#"API Error: 404 {"type":"error","error":{"type":"not_found_error","message":"model: claude-nope"}}"#
+            ),
+            ModelCheckFailure::UnknownModel
+        );
+        assert_eq!(
+            classify_model_check_failure(
+                r#"API Error: 529 {"type":"error","error":{"type":"overloaded_error","message":"Overloaded"}}"#
+            ),
+            ModelCheckFailure::Transient
+        );
+        assert_eq!(
+            classify_model_check_failure("API Error: 429 rate_limit_error for model opus"),
+            ModelCheckFailure::Transient
+        );
+        // Mentioning a model is not enough to call it unknown
+        assert_eq!(
+            classify_model_check_failure("Invalid API key · Please run /login to use model opus"),
+            ModelCheckFailure::Other
+        );
+    }
+
+    #[test]
+    fn test_summarize_session_file_skips_malformed_lines() {
+        let temp_dir = TempDir::new().unwrap();
+        let content = concat!(
+            r#"{"type":"summary","summary":"Fix bug"}"#,
+            "\n",
+            r#"{"type":"user","timestamp":"2025-01-01T10:00:00Z","message":{"role":"user","content":"hi"}}"#,
+            "\n",
+            "not json\n",
+            "\n",
+            r#"{"type":"assistant","timestamp":"2025-01-01T10:05:00Z","message":{"role":"assistant","content":[{"type":"text","text":"hello"}]}}"#,
+            "\n",
+            r#"{"type":"user","timestamp":"2025-01-01T10:06"#,
+        );
+        create_test_session_file(&temp_dir.path().to_path_buf(), "abc.jsonl", content).unwrap();
+
+        let (started_at, message_count, last_activity, malformed_lines) =
+            summarize_session_file(&temp_dir.path().join("abc.jsonl"));
+        assert_eq!(started_at.as_deref(), Some("2025-01-01T10:00:00Z"));
+        assert_eq!(last_activity.as_deref(), Some("2025-01-01T10:05:00Z"));
+        assert_eq!(message_count, 2);
+        assert_eq!(malformed_lines, 2);
+
+        let sessions = list_sessions_in_project_dir(temp_dir.path(), "-tmp-proj", "/tmp/proj");
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].session_id, "abc");
+    }
+
     /// Helper function to create a test session file
     fn create_test_session_file(
         dir: &PathBuf,
@@ -1,12 +1,16 @@
 use anyhow::{Context, Result};
 use dirs;
-use log::{error, info};
+use log::{error, info, warn};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 use std::process::Command;
-use tauri::AppHandle;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::agents::{read_setting, write_setting, AgentDb};
 
 /// Helper function to create a std::process::Command with proper environment variables
 /// This ensures commands like Claude can find Node.js and other dependencies
@@ -64,11 +68,41 @@ pub struct MCPProjectConfig {
 /// Individual server configuration in .mcp.json
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MCPServerConfig {
-    pub command: String,
+    /// Transport type: "stdio" (default when omitted), "sse" or "http"
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub transport: Option<String>,
+    /// Command to execute (for stdio)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
     #[serde(default)]
     pub args: Vec<String>,
     #[serde(default)]
     pub env: HashMap<String, String>,
+    /// URL endpoint (for sse/http)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// HTTP headers sent to the endpoint (for sse/http)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
+}
+
+/// Validation result for a single server in a .mcp.json file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MCPServerValidation {
+    pub name: String,
+    pub transport: String,
+    pub valid: bool,
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+/// Validation report for a whole .mcp.json file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MCPConfigValidationReport {
+    pub valid: bool,
+    /// Set when the document could not be parsed at all
+    pub parse_error: Option<String>,
+    pub servers: Vec<MCPServerValidation>,
 }
 
 /// Result of adding a server
@@ -93,20 +127,324 @@ pub struct ImportServerResult {
     pub name: String,
     pub success: bool,
     pub error: Option<String>,
+    /// Changes and warnings from normalizing the server's env values
+    #[serde(default)]
+    pub notes: Vec<String>,
+}
+
+/// Health of a single MCP server as reported by a health-check sweep
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MCPHealthStatus {
+    pub reachable: bool,
+    pub latency_ms: u64,
+    pub error: Option<String>,
+}
+
+/// Whether the `claude` binary at a path accepts `mcp list --json`, detected once per binary
+static MCP_LIST_JSON_SUPPORT: Mutex<Option<(String, bool)>> = Mutex::new(None);
+
+/// A server entry as emitted by `claude mcp list --json`
+#[derive(Debug, Deserialize)]
+struct MCPListJsonEntry {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(rename = "type", alias = "transport", default)]
+    transport: Option<String>,
+    #[serde(default)]
+    command: Option<String>,
+    #[serde(default)]
+    args: Vec<String>,
+    #[serde(default)]
+    env: HashMap<String, String>,
+    #[serde(default)]
+    url: Option<String>,
+    #[serde(default)]
+    scope: Option<String>,
+}
+
+/// `claude mcp list --json` output, either a list of entries or a map keyed by server name
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum MCPListJson {
+    List(Vec<MCPListJsonEntry>),
+    // Tried before `Map`, which would otherwise accept the wrapper key as a server
+    Wrapped {
+        #[serde(rename = "mcpServers", alias = "servers")]
+        servers: HashMap<String, MCPListJsonEntry>,
+    },
+    Map(HashMap<String, MCPListJsonEntry>),
+}
+
+/// Convert `claude mcp list --json` output into servers
+fn parse_mcp_list_json(output: &str) -> Result<Vec<MCPServer>> {
+    let parsed: MCPListJson =
+        serde_json::from_str(output.trim()).context("Invalid JSON from claude mcp list")?;
+
+    let entries: Vec<(Option<String>, MCPListJsonEntry)> = match parsed {
+        MCPListJson::List(list) => list.into_iter().map(|e| (None, e)).collect(),
+        MCPListJson::Map(map) | MCPListJson::Wrapped { servers: map } => {
+            map.into_iter().map(|(k, e)| (Some(k), e)).collect()
+        }
+    };
+
+    let mut servers: Vec<MCPServer> = entries
+        .into_iter()
+        .filter_map(|(key, entry)| {
+            let name = entry.name.or(key)?;
+            let transport = entry
+                .transport
+                .unwrap_or_else(|| if entry.url.is_some() { "sse" } else { "stdio" }.to_string());
+            Some(MCPServer {
+                name,
+                transport,
+                command: entry.command,
+                args: entry.args,
+                env: entry.env,
+                url: entry.url,
+                scope: entry.scope.unwrap_or_else(|| "local".to_string()),
+                is_active: false,
+                status: ServerStatus {
+                    running: false,
+                    error: None,
+                    last_checked: None,
+                },
+            })
+        })
+        .collect();
+    servers.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(servers)
+}
+
+/// Record whether the binary at `path` accepts `mcp list --json`
+fn set_mcp_list_json_support(path: &str, supported: bool) {
+    if let Ok(mut cached) = MCP_LIST_JSON_SUPPORT.lock() {
+        *cached = Some((path.to_string(), supported));
+    }
+}
+
+/// Whether the selected `claude` accepts `mcp list --json`
+///
+/// Detected from `claude mcp list --help` the first time a binary is used.
+async fn mcp_list_supports_json(app: &AppHandle, path: &str) -> bool {
+    let cached = MCP_LIST_JSON_SUPPORT
+        .lock()
+        .ok()
+        .and_then(|cached| cached.clone())
+        .filter(|(cached_path, _)| cached_path == path);
+    if let Some((_, supported)) = cached {
+        return supported;
+    }
+
+    let supported = execute_claude_mcp_command(app, vec!["list", "--help"])
+        .await
+        .map(|help| help.contains("--json"))
+        .unwrap_or(false);
+    info!("claude mcp list --json supported: {}", supported);
+    set_mcp_list_json_support(path, supported);
+    supported
+}
+
+/// Lists servers via `claude mcp list --json` when the installed Claude supports it
+///
+/// Returns None when `--json` isn't supported or the output can't be parsed, so the
+/// caller can fall back to the text parser. A failed `--json` run marks the binary
+/// as unsupported so later lists go straight to text.
+async fn mcp_list_json(app: &AppHandle) -> Option<Vec<MCPServer>> {
+    let app_handle = app.clone();
+    let path = tokio::task::spawn_blocking(move || find_claude_binary(&app_handle).ok())
+        .await
+        .ok()
+        .flatten()?;
+
+    if !mcp_list_supports_json(app, &path).await {
+        return None;
+    }
+
+    let output = match execute_claude_mcp_command(app, vec!["list", "--json"]).await {
+        Ok(output) => output,
+        Err(e) => {
+            warn!("'claude mcp list --json' failed, using text output: {}", e);
+            set_mcp_list_json_support(&path, false);
+            return None;
+        }
+    };
+
+    match parse_mcp_list_json(&output) {
+        Ok(servers) => Some(servers),
+        Err(e) => {
+            warn!("Could not parse JSON server list, using text output: {}", e);
+            None
+        }
+    }
+}
+
+/// Unparsed result of a `claude mcp` invocation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MCPRawOutput {
+    pub success: bool,
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// How long a health-check sweep result is served from cache
+const HEALTH_CHECK_CACHE_TTL: Duration = Duration::from_secs(15);
+
+/// Overall deadline for a health-check sweep; servers still pending are reported as timed out
+const HEALTH_CHECK_DEADLINE: Duration = Duration::from_secs(20);
+
+/// Most recent health-check sweep, shared between polls
+#[derive(Default)]
+pub struct MCPHealthCache(pub Mutex<Option<(Instant, HashMap<String, MCPHealthStatus>)>>);
+
+impl MCPHealthCache {
+    /// Drop the cached sweep so the next poll probes the servers again
+    pub fn clear(&self) {
+        if let Ok(mut cache) = self.0.lock() {
+            *cache = None;
+        }
+    }
+}
+
+/// How often watched MCP config files are checked for changes
+const MCP_WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Opt-in background watcher for MCP config files, started by `mcp_watch_config`
+#[derive(Default)]
+pub struct MCPConfigWatcher(pub Mutex<Option<tauri::async_runtime::JoinHandle<()>>>);
+
+impl MCPConfigWatcher {
+    /// Stop the watcher if one is running
+    pub fn stop(&self) {
+        if let Ok(mut handle) = self.0.lock() {
+            if let Some(handle) = handle.take() {
+                handle.abort();
+                info!("Stopped MCP config watcher");
+            }
+        }
+    }
+}
+
+impl Drop for MCPConfigWatcher {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Default number of attempts made to locate the Claude binary
+const DEFAULT_MCP_RETRY_ATTEMPTS: u32 = 3;
+
+/// Default delay before the first retry; doubled after each further failure
+const DEFAULT_MCP_RETRY_DELAY_MS: u64 = 200;
+
+/// Retry policy for locating the Claude binary before running an MCP command
+#[derive(Debug, Clone, Copy)]
+pub struct McpRetryConfig {
+    pub max_attempts: u32,
+    pub delay: Duration,
+}
+
+impl Default for McpRetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: DEFAULT_MCP_RETRY_ATTEMPTS,
+            delay: Duration::from_millis(DEFAULT_MCP_RETRY_DELAY_MS),
+        }
+    }
+}
+
+impl McpRetryConfig {
+    /// Reads the `mcp_retry_attempts` and `mcp_retry_delay_ms` settings, falling back
+    /// to the defaults for missing or invalid values
+    fn from_settings(app: &AppHandle) -> Self {
+        let mut config = Self::default();
+        let Some(db) = app.try_state::<AgentDb>() else {
+            return config;
+        };
+        let Ok(conn) = db.0.lock() else {
+            return config;
+        };
+
+        if let Some(attempts) = read_setting(&conn, "mcp_retry_attempts")
+            .ok()
+            .flatten()
+            .and_then(|v| v.parse::<u32>().ok())
+        {
+            config.max_attempts = attempts.max(1);
+        }
+        if let Some(delay_ms) = read_setting(&conn, "mcp_retry_delay_ms")
+            .ok()
+            .flatten()
+            .and_then(|v| v.parse::<u64>().ok())
+        {
+            config.delay = Duration::from_millis(delay_ms);
+        }
+
+        config
+    }
+}
+
+/// Runs `op` until it succeeds or `config.max_attempts` attempts have failed,
+/// sleeping asynchronously with exponential backoff between attempts
+async fn retry_with_backoff<T, F, Fut>(config: McpRetryConfig, mut op: F) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<T>>,
+{
+    let max_attempts = config.max_attempts.max(1);
+    let mut delay = config.delay;
+    let mut attempt = 1;
+
+    loop {
+        match op().await {
+            Ok(value) => return Ok(value),
+            Err(e) if attempt >= max_attempts => return Err(e),
+            Err(e) => {
+                warn!(
+                    "Attempt {}/{} failed: {}; retrying in {:?}",
+                    attempt, max_attempts, e, delay
+                );
+                tokio::time::sleep(delay).await;
+                delay *= 2;
+                attempt += 1;
+            }
+        }
+    }
 }
 
-/// Executes a claude mcp command
-fn execute_claude_mcp_command(app_handle: &AppHandle, args: Vec<&str>) -> Result<String> {
+/// Runs a claude mcp command and returns its raw process output
+async fn run_claude_mcp_command(
+    app_handle: &AppHandle,
+    args: Vec<&str>,
+) -> Result<std::process::Output> {
     info!("Executing claude mcp command with args: {:?}", args);
 
-    let claude_path = find_claude_binary(app_handle)?;
+    let retry = McpRetryConfig::from_settings(app_handle);
+    let claude_path = retry_with_backoff(retry, || {
+        // Binary discovery runs `which`/`--version` subprocesses
+        let app = app_handle.clone();
+        async move {
+            tokio::task::spawn_blocking(move || find_claude_binary(&app))
+                .await
+                .context("Claude binary lookup task failed")?
+        }
+    })
+    .await?;
     let mut cmd = create_command_with_env(&claude_path);
     cmd.arg("mcp");
     for arg in args {
         cmd.arg(arg);
     }
 
-    let output = cmd.output().context("Failed to execute claude command")?;
+    tokio::task::spawn_blocking(move || cmd.output())
+        .await
+        .context("Claude command task failed")?
+        .context("Failed to execute claude command")
+}
+
+/// Executes a claude mcp command, returning stdout on success
+async fn execute_claude_mcp_command(app_handle: &AppHandle, args: Vec<&str>) -> Result<String> {
+    let output = run_claude_mcp_command(app_handle, args).await?;
 
     if output.status.success() {
         Ok(String::from_utf8_lossy(&output.stdout).to_string())
@@ -116,79 +454,131 @@ fn execute_claude_mcp_command(app_handle: &AppHandle, args: Vec<&str>) -> Result
     }
 }
 
-/// Adds a new MCP server
-#[tauri::command]
-pub async fn mcp_add(
-    app: AppHandle,
-    name: String,
-    transport: String,
-    command: Option<String>,
-    args: Vec<String>,
-    env: HashMap<String, String>,
-    url: Option<String>,
-    scope: String,
-) -> Result<AddServerResult, String> {
-    info!("Adding MCP server: {} with transport: {}", name, transport);
+/// Scopes accepted by `claude mcp add`
+const MCP_SCOPES: [&str; 3] = ["local", "project", "user"];
+
+/// Resolves the scope for adding a server, falling back to the `default_mcp_scope`
+/// setting (or "local") when none is given, and rejecting unknown scopes
+fn resolve_mcp_scope(app: &AppHandle, scope: &str) -> Result<String, String> {
+    let scope = if scope.trim().is_empty() {
+        app.try_state::<AgentDb>()
+            .and_then(|db| {
+                let conn = db.0.lock().ok()?;
+                read_setting(&conn, "default_mcp_scope").ok().flatten()
+            })
+            .unwrap_or_else(|| "local".to_string())
+    } else {
+        scope.trim().to_string()
+    };
 
-    // Prepare owned strings for environment variables
-    let env_args: Vec<String> = env
-        .iter()
-        .map(|(key, value)| format!("{}={}", key, value))
-        .collect();
+    if !MCP_SCOPES.contains(&scope.as_str()) {
+        return Err(format!(
+            "Invalid scope '{}'. Must be one of: local, project, user",
+            scope
+        ));
+    }
+
+    Ok(scope)
+}
 
-    let mut cmd_args = vec!["add"];
+/// Builds the `claude mcp add` argument vector (without the leading "mcp")
+fn build_mcp_add_args(
+    name: &str,
+    transport: &str,
+    command: Option<&str>,
+    args: &[String],
+    env: &HashMap<String, String>,
+    url: Option<&str>,
+    scope: &str,
+) -> Result<Vec<String>, String> {
+    let mut cmd_args = vec!["add".to_string()];
 
     // Add scope flag
-    cmd_args.push("-s");
-    cmd_args.push(&scope);
+    cmd_args.push("-s".to_string());
+    cmd_args.push(scope.to_string());
 
     // Add transport flag for SSE
     if transport == "sse" {
-        cmd_args.push("--transport");
-        cmd_args.push("sse");
+        cmd_args.push("--transport".to_string());
+        cmd_args.push("sse".to_string());
     }
 
     // Add environment variables
-    for (i, _) in env.iter().enumerate() {
-        cmd_args.push("-e");
-        cmd_args.push(&env_args[i]);
+    for (key, value) in env {
+        cmd_args.push("-e".to_string());
+        cmd_args.push(format!("{}={}", key, value));
     }
 
     // Add name
-    cmd_args.push(&name);
+    cmd_args.push(name.to_string());
 
     // Add command/URL based on transport
     if transport == "stdio" {
-        if let Some(cmd) = &command {
-            // Add "--" separator before command to prevent argument parsing issues
-            if !args.is_empty() || cmd.contains('-') {
-                cmd_args.push("--");
-            }
-            cmd_args.push(cmd);
-            // Add arguments
-            for arg in &args {
-                cmd_args.push(arg);
-            }
-        } else {
-            return Ok(AddServerResult {
-                success: false,
-                message: "Command is required for stdio transport".to_string(),
-                server_name: None,
-            });
+        let cmd = command.ok_or("Command is required for stdio transport")?;
+        // Add "--" separator before command to prevent argument parsing issues
+        if !args.is_empty() || cmd.contains('-') {
+            cmd_args.push("--".to_string());
         }
+        cmd_args.push(cmd.to_string());
+        // Add arguments
+        cmd_args.extend(args.iter().cloned());
     } else if transport == "sse" {
-        if let Some(url_str) = &url {
-            cmd_args.push(url_str);
-        } else {
+        let url_str = url.ok_or("URL is required for SSE transport")?;
+        cmd_args.push(url_str.to_string());
+    }
+
+    Ok(cmd_args)
+}
+
+/// Quotes an argument for display in a shell-like command line
+fn shell_quote(arg: &str) -> String {
+    let is_plain = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./:=@,+".contains(c));
+    if is_plain {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    }
+}
+
+/// Adds a new MCP server
+#[tauri::command]
+pub async fn mcp_add(
+    app: AppHandle,
+    name: String,
+    transport: String,
+    command: Option<String>,
+    args: Vec<String>,
+    env: HashMap<String, String>,
+    url: Option<String>,
+    scope: String,
+) -> Result<AddServerResult, String> {
+    info!("Adding MCP server: {} with transport: {}", name, transport);
+
+    let cmd_args = match resolve_mcp_scope(&app, &scope).and_then(|scope| {
+        build_mcp_add_args(
+            &name,
+            &transport,
+            command.as_deref(),
+            &args,
+            &env,
+            url.as_deref(),
+            &scope,
+        )
+    }) {
+        Ok(cmd_args) => cmd_args,
+        Err(message) => {
             return Ok(AddServerResult {
                 success: false,
-                message: "URL is required for SSE transport".to_string(),
+                message,
                 server_name: None,
             });
         }
-    }
+    };
 
-    match execute_claude_mcp_command(&app, cmd_args) {
+    match execute_claude_mcp_command(&app, cmd_args.iter().map(String::as_str).collect()).await {
         Ok(output) => {
             info!("Successfully added MCP server: {}", name);
             Ok(AddServerResult {
@@ -208,12 +598,54 @@ pub async fn mcp_add(
     }
 }
 
+/// Returns the exact `claude mcp add` command line that `mcp_add` would run, without running it
+#[tauri::command]
+pub async fn mcp_preview_add(
+    app: AppHandle,
+    name: String,
+    transport: String,
+    command: Option<String>,
+    args: Vec<String>,
+    env: HashMap<String, String>,
+    url: Option<String>,
+    scope: String,
+) -> Result<String, String> {
+    let scope = resolve_mcp_scope(&app, &scope)?;
+    let cmd_args = build_mcp_add_args(
+        &name,
+        &transport,
+        command.as_deref(),
+        &args,
+        &env,
+        url.as_deref(),
+        &scope,
+    )?;
+
+    // Binary discovery runs `which`/`--version` subprocesses
+    let app_handle = app.clone();
+    let claude_path = tokio::task::spawn_blocking(move || find_claude_binary(&app_handle).ok())
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| "claude".to_string());
+
+    let mut parts = vec![shell_quote(&claude_path), "mcp".to_string()];
+    parts.extend(cmd_args.iter().map(|arg| shell_quote(arg)));
+
+    Ok(parts.join(" "))
+}
+
 /// Lists all configured MCP servers
 #[tauri::command]
 pub async fn mcp_list(app: AppHandle) -> Result<Vec<MCPServer>, String> {
     info!("Listing MCP servers");
 
-    match execute_claude_mcp_command(&app, vec!["list"]) {
+    if let Some(servers) = mcp_list_json(&app).await {
+        info!("Found {} MCP servers via JSON output", servers.len());
+        return Ok(servers);
+    }
+
+    match execute_claude_mcp_command(&app, vec!["list"]).await {
         Ok(output) => {
             info!("Raw output from 'claude mcp list': {:?}", output);
             let trimmed = output.trim();
@@ -330,12 +762,29 @@ pub async fn mcp_list(app: AppHandle) -> Result<Vec<MCPServer>, String> {
     }
 }
 
+/// Returns the unparsed output of `claude mcp list`, for debugging the parser
+#[tauri::command]
+pub async fn mcp_list_raw(app: AppHandle) -> Result<MCPRawOutput, String> {
+    info!("Running raw 'claude mcp list'");
+
+    let output = run_claude_mcp_command(&app, vec!["list"])
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(MCPRawOutput {
+        success: output.status.success(),
+        exit_code: output.status.code(),
+        stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+        stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+    })
+}
+
 /// Gets details for a specific MCP server
 #[tauri::command]
 pub async fn mcp_get(app: AppHandle, name: String) -> Result<MCPServer, String> {
     info!("Getting MCP server details for: {}", name);
 
-    match execute_claude_mcp_command(&app, vec!["get", &name]) {
+    match execute_claude_mcp_command(&app, vec!["get", &name]).await {
         Ok(output) => {
             // Parse the structured text output
             let mut scope = "local".to_string();
@@ -404,7 +853,7 @@ pub async fn mcp_get(app: AppHandle, name: String) -> Result<MCPServer, String>
 pub async fn mcp_remove(app: AppHandle, name: String) -> Result<String, String> {
     info!("Removing MCP server: {}", name);
 
-    match execute_claude_mcp_command(&app, vec!["remove", &name]) {
+    match execute_claude_mcp_command(&app, vec!["remove", &name]).await {
         Ok(output) => {
             info!("Successfully removed MCP server: {}", name);
             Ok(output.trim().to_string())
@@ -429,6 +878,17 @@ pub async fn mcp_add_json(
         name, scope
     );
 
+    let scope = match resolve_mcp_scope(&app, &scope) {
+        Ok(scope) => scope,
+        Err(message) => {
+            return Ok(AddServerResult {
+                success: false,
+                message,
+                server_name: None,
+            });
+        }
+    };
+
     // Build command args
     let mut cmd_args = vec!["add-json", &name, &json_config];
 
@@ -437,7 +897,7 @@ pub async fn mcp_add_json(
     cmd_args.push(scope_flag);
     cmd_args.push(&scope);
 
-    match execute_claude_mcp_command(&app, cmd_args) {
+    match execute_claude_mcp_command(&app, cmd_args).await {
         Ok(output) => {
             info!("Successfully added MCP server from JSON: {}", name);
             Ok(AddServerResult {
@@ -457,57 +917,492 @@ pub async fn mcp_add_json(
     }
 }
 
-/// Imports MCP servers from Claude Desktop
+/// Environment variables that change how a process loads code, never set from a link silently
+const SENSITIVE_LINK_ENV_KEYS: [&str; 7] = [
+    "PATH",
+    "LD_PRELOAD",
+    "LD_LIBRARY_PATH",
+    "DYLD_INSERT_LIBRARIES",
+    "DYLD_LIBRARY_PATH",
+    "NODE_OPTIONS",
+    "PYTHONPATH",
+];
+
+/// Parses an `mcp://` or `https://` install link into a server name and config
+///
+/// The config is read from the `config` query parameter, either as JSON or
+/// base64-encoded JSON. The name comes from the `name` parameter or a `name`
+/// field in the config.
+fn parse_mcp_install_link(link: &str) -> Result<(String, MCPServerConfig), String> {
+    use base64::Engine as _;
+
+    let url = reqwest::Url::parse(link.trim()).map_err(|e| format!("Invalid link: {}", e))?;
+    if url.scheme() != "mcp" && url.scheme() != "https" {
+        return Err(format!("Unsupported link scheme: {}", url.scheme()));
+    }
+
+    let mut name = None;
+    let mut raw_config = None;
+    for (key, value) in url.query_pairs() {
+        match key.as_ref() {
+            "name" => name = Some(value.into_owned()),
+            "config" => raw_config = Some(value.into_owned()),
+            _ => {}
+        }
+    }
+    let raw_config = raw_config.ok_or("Link has no \"config\" parameter")?;
+
+    let json = if raw_config.trim_start().starts_with('{') {
+        raw_config
+    } else {
+        let encoded = raw_config.trim().trim_end_matches('=');
+        let bytes = base64::engine::general_purpose::URL_SAFE_NO_PAD
+            .decode(encoded)
+            .or_else(|_| base64::engine::general_purpose::STANDARD_NO_PAD.decode(encoded))
+            .map_err(|e| format!("Link config is neither JSON nor base64: {}", e))?;
+        String::from_utf8(bytes).map_err(|e| format!("Link config is not UTF-8: {}", e))?
+    };
+
+    let mut value: serde_json::Value =
+        serde_json::from_str(&json).map_err(|e| format!("Invalid config JSON: {}", e))?;
+    let embedded_name = value
+        .as_object_mut()
+        .and_then(|obj| obj.remove("name"))
+        .and_then(|n| n.as_str().map(str::to_string));
+    let config: MCPServerConfig =
+        serde_json::from_value(value).map_err(|e| format!("Invalid server config: {}", e))?;
+
+    let name = name
+        .or(embedded_name)
+        .filter(|n| !n.trim().is_empty())
+        .ok_or("Link does not name the server")?;
+    Ok((name, config))
+}
+
+/// Environment entries from a link that need explicit confirmation: loader-affecting
+/// variables and values that point at absolute paths
+fn suspicious_link_env(config: &MCPServerConfig) -> Vec<String> {
+    let mut keys: Vec<String> = config
+        .env
+        .iter()
+        .filter(|(key, value)| {
+            let value = value.trim();
+            let is_absolute_path =
+                value.starts_with('/') || value.starts_with('~') || value.get(1..3) == Some(":\\");
+            SENSITIVE_LINK_ENV_KEYS.contains(&key.to_uppercase().as_str()) || is_absolute_path
+        })
+        .map(|(key, _)| key.clone())
+        .collect();
+    keys.sort();
+    keys
+}
+
+/// Adds an MCP server from a shareable `mcp://` or `https://` install link
+///
+/// Links that set loader-affecting or absolute-path environment variables are
+/// refused unless `confirmed` is set.
 #[tauri::command]
-pub async fn mcp_add_from_claude_desktop(
+pub async fn mcp_add_from_url(
     app: AppHandle,
+    link: String,
     scope: String,
-) -> Result<ImportResult, String> {
-    info!(
-        "Importing MCP servers from Claude Desktop with scope: {}",
-        scope
-    );
+    confirmed: Option<bool>,
+) -> Result<AddServerResult, String> {
+    info!("Adding MCP server from link");
 
-    // Get Claude Desktop config path based on platform
-    let config_path = if cfg!(target_os = "macos") {
-        dirs::home_dir()
-            .ok_or_else(|| "Could not find home directory".to_string())?
-            .join("Library")
-            .join("Application Support")
-            .join("Claude")
-            .join("claude_desktop_config.json")
-    } else if cfg!(target_os = "linux") {
-        // For WSL/Linux, check common locations
-        dirs::config_dir()
-            .ok_or_else(|| "Could not find config directory".to_string())?
-            .join("Claude")
-            .join("claude_desktop_config.json")
-    } else {
-        return Err(
-            "Import from Claude Desktop is only supported on macOS and Linux/WSL".to_string(),
-        );
+    let failure = |message: String| AddServerResult {
+        success: false,
+        message,
+        server_name: None,
     };
 
-    // Check if config file exists
-    if !config_path.exists() {
-        return Err(
-            "Claude Desktop configuration not found. Make sure Claude Desktop is installed."
-                .to_string(),
-        );
+    let (name, config) = match parse_mcp_install_link(&link) {
+        Ok(parsed) => parsed,
+        Err(message) => return Ok(failure(message)),
+    };
+
+    let validation = validate_server_config(&name, &config);
+    if !validation.valid {
+        return Ok(failure(validation.errors.join("; ")));
     }
 
-    // Read and parse the config file
-    let config_content = fs::read_to_string(&config_path)
-        .map_err(|e| format!("Failed to read Claude Desktop config: {}", e))?;
+    let suspicious = suspicious_link_env(&config);
+    if !suspicious.is_empty() && !confirmed.unwrap_or(false) {
+        warn!(
+            "Link for MCP server '{}' sets sensitive environment: {:?}",
+            name, suspicious
+        );
+        return Ok(failure(format!(
+            "Link sets environment variables that need confirmation: {}",
+            suspicious.join(", ")
+        )));
+    }
 
-    let config: serde_json::Value = serde_json::from_str(&config_content)
-        .map_err(|e| format!("Failed to parse Claude Desktop config: {}", e))?;
+    let json_config = serde_json::to_string(&config)
+        .map_err(|e| format!("Failed to serialize server config: {}", e))?;
+    mcp_add_json(app, name, json_config, scope).await
+}
 
-    // Extract MCP servers
-    let mcp_servers = config
-        .get("mcpServers")
-        .and_then(|v| v.as_object())
-        .ok_or_else(|| "No MCP servers found in Claude Desktop config".to_string())?;
+/// A server as configured in one scope, including env vars and headers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MCPSnapshotServer {
+    pub name: String,
+    pub scope: String,
+    pub config: MCPServerConfig,
+}
+
+/// A named copy of the full MCP server configuration, kept in app settings
+///
+/// Taken from the config files rather than `mcp_list`, so env vars (including
+/// API keys) and headers are captured and can be restored.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MCPConfigSnapshot {
+    pub label: String,
+    pub created_at: String,
+    pub servers: Vec<MCPSnapshotServer>,
+}
+
+/// What `mcp_restore_config` changed
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MCPRestoreReport {
+    /// Servers removed or replaced, as `name (scope)`
+    pub removed: Vec<String>,
+    /// Snapshot servers added back, as `name (scope)`
+    pub added: Vec<String>,
+    /// Snapshot servers already configured identically
+    pub unchanged: Vec<String>,
+    /// Servers missing from the snapshot, left in place because an addition failed
+    pub kept: Vec<String>,
+    /// Removals or additions that failed, with the reason
+    pub errors: Vec<String>,
+}
+
+/// Settings key a snapshot is stored under
+fn mcp_snapshot_key(label: &str) -> Result<String, String> {
+    let label = label.trim();
+    if label.is_empty() {
+        return Err("Snapshot label cannot be empty".to_string());
+    }
+    Ok(format!("mcp_snapshot:{}", label))
+}
+
+/// Servers configured in every scope, as `claude mcp` run from `project_dir` sees them
+///
+/// User servers come from `mcpServers` in `~/.claude.json`, local servers from its
+/// entry for `project_dir`, and project servers from `project_dir/.mcp.json`.
+fn configured_servers(
+    claude_json: &serde_json::Value,
+    project_dir: &std::path::Path,
+) -> Vec<MCPSnapshotServer> {
+    let project_key = project_dir.to_string_lossy();
+    let project_json: serde_json::Value = fs::read_to_string(project_dir.join(".mcp.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or(serde_json::Value::Null);
+
+    let layers = [
+        ("user", claude_json.get("mcpServers")),
+        (
+            "local",
+            claude_json
+                .get("projects")
+                .and_then(|projects| projects.get(project_key.trim_end_matches('/')))
+                .and_then(|project| project.get("mcpServers")),
+        ),
+        ("project", project_json.get("mcpServers")),
+    ];
+
+    let mut servers: Vec<MCPSnapshotServer> = layers
+        .into_iter()
+        .flat_map(|(scope, value)| {
+            parse_server_map(value, scope)
+                .into_iter()
+                .map(move |(name, config)| MCPSnapshotServer {
+                    name,
+                    scope: scope.to_string(),
+                    config,
+                })
+        })
+        .collect();
+    servers.sort_by(|a, b| (&a.scope, &a.name).cmp(&(&b.scope, &b.name)));
+    servers
+}
+
+/// Directory `claude mcp` commands run from, which decides the local and project scopes
+fn mcp_command_dir() -> Result<PathBuf, String> {
+    std::env::current_dir().map_err(|e| format!("Failed to get current directory: {}", e))
+}
+
+/// Whether two configs would produce the same server
+fn same_server_config(a: &MCPServerConfig, b: &MCPServerConfig) -> bool {
+    serde_json::to_value(a).ok() == serde_json::to_value(b).ok()
+}
+
+/// Add a snapshot server with `mcp_add_json`
+async fn add_snapshot_server(app: &AppHandle, server: &MCPSnapshotServer) -> Result<(), String> {
+    let json_config = serde_json::to_string(&server.config)
+        .map_err(|e| format!("Failed to serialize server config: {}", e))?;
+    let result = mcp_add_json(
+        app.clone(),
+        server.name.clone(),
+        json_config,
+        server.scope.clone(),
+    )
+    .await?;
+    if result.success {
+        Ok(())
+    } else {
+        Err(result.message)
+    }
+}
+
+/// Remove a server from one scope, leaving same-named servers in other scopes alone
+pub(crate) async fn mcp_remove_scoped(
+    app: &AppHandle,
+    name: &str,
+    scope: &str,
+) -> Result<(), String> {
+    execute_claude_mcp_command(app, vec!["remove", name, "-s", scope])
+        .await
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// Remove a configured server from its scope
+async fn remove_snapshot_server(app: &AppHandle, server: &MCPSnapshotServer) -> Result<(), String> {
+    mcp_remove_scoped(app, &server.name, &server.scope).await
+}
+
+/// Save the current MCP servers of every scope as a named snapshot
+///
+/// An existing snapshot with the same label is replaced. Servers are read from
+/// `~/.claude.json` and `.mcp.json` with their env vars and headers, so the
+/// snapshot holds any API keys they contain.
+#[tauri::command]
+pub async fn mcp_snapshot_config(
+    app: AppHandle,
+    label: String,
+) -> Result<MCPConfigSnapshot, String> {
+    let key = mcp_snapshot_key(&label)?;
+    info!("Snapshotting MCP configuration as '{}'", label.trim());
+
+    let snapshot = MCPConfigSnapshot {
+        label: label.trim().to_string(),
+        created_at: chrono::Utc::now().to_rfc3339(),
+        servers: configured_servers(&read_claude_json()?, &mcp_command_dir()?),
+    };
+    let value = serde_json::to_string(&snapshot)
+        .map_err(|e| format!("Failed to serialize snapshot: {}", e))?;
+
+    let db = app.state::<AgentDb>();
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    write_setting(&conn, &key, &value)?;
+
+    Ok(snapshot)
+}
+
+/// Replace the current MCP servers with a saved snapshot
+///
+/// Snapshot servers are added first; a server whose name already exists in the
+/// same scope with a different config is replaced, and put back if the new one
+/// can't be added. Servers missing from the snapshot are removed only once every
+/// addition succeeded. Failures are collected in the report rather than aborting.
+#[tauri::command]
+pub async fn mcp_restore_config(app: AppHandle, label: String) -> Result<MCPRestoreReport, String> {
+    let key = mcp_snapshot_key(&label)?;
+    info!(
+        "Restoring MCP configuration from snapshot '{}'",
+        label.trim()
+    );
+
+    let stored = {
+        let db = app.state::<AgentDb>();
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        read_setting(&conn, &key)?
+    }
+    .ok_or_else(|| format!("No MCP snapshot named '{}'", label.trim()))?;
+    let snapshot: MCPConfigSnapshot = serde_json::from_str(&stored).map_err(|e| {
+        format!(
+            "Failed to parse snapshot (snapshots from older versions must be retaken): {}",
+            e
+        )
+    })?;
+
+    let current = configured_servers(&read_claude_json()?, &mcp_command_dir()?);
+    let same_key =
+        |a: &MCPSnapshotServer, b: &MCPSnapshotServer| a.name == b.name && a.scope == b.scope;
+    let mut report = MCPRestoreReport::default();
+
+    for server in &snapshot.servers {
+        let entry = format!("{} ({})", server.name, server.scope);
+        let existing = current.iter().find(|c| same_key(c, server));
+        if let Some(existing) = existing {
+            if same_server_config(&existing.config, &server.config) {
+                report.unchanged.push(entry);
+                continue;
+            }
+            // A name can't be added twice to one scope, so this one is replaced in place
+            if let Err(e) = remove_snapshot_server(&app, existing).await {
+                report.errors.push(format!("replace {}: {}", entry, e));
+                continue;
+            }
+        }
+
+        match add_snapshot_server(&app, server).await {
+            Ok(()) => {
+                if existing.is_some() {
+                    report.removed.push(entry.clone());
+                }
+                report.added.push(entry);
+            }
+            Err(e) => {
+                let rollback = match existing {
+                    Some(existing) => match add_snapshot_server(&app, existing).await {
+                        Ok(()) => "; previous config kept".to_string(),
+                        Err(e) => format!("; previous config could not be restored: {}", e),
+                    },
+                    None => String::new(),
+                };
+                report
+                    .errors
+                    .push(format!("add {}: {}{}", entry, e, rollback));
+            }
+        }
+    }
+
+    let stale = current
+        .iter()
+        .filter(|c| !snapshot.servers.iter().any(|s| same_key(c, s)));
+    for server in stale {
+        let entry = format!("{} ({})", server.name, server.scope);
+        if !report.errors.is_empty() {
+            report.kept.push(entry);
+            continue;
+        }
+        match remove_snapshot_server(&app, server).await {
+            Ok(()) => report.removed.push(entry),
+            Err(e) => report.errors.push(format!("remove {}: {}", entry, e)),
+        }
+    }
+
+    app.state::<MCPHealthCache>().clear();
+    info!("Restored MCP snapshot '{}': {:?}", snapshot.label, report);
+    Ok(report)
+}
+
+/// Trim env values, stringify scalars and check `${VAR}` placeholders
+///
+/// With `expand`, placeholders are replaced using `lookup`; unresolved ones are
+/// left in place. Returns the normalized env and a note per change or warning.
+fn normalize_env_values(
+    env: &serde_json::Map<String, serde_json::Value>,
+    expand: bool,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> (serde_json::Map<String, serde_json::Value>, Vec<String>) {
+    let placeholder = regex::Regex::new(r"\$\{([A-Za-z_][A-Za-z0-9_]*)\}").unwrap();
+    let mut normalized = serde_json::Map::new();
+    let mut notes = Vec::new();
+
+    for (key, value) in env {
+        let raw = match value {
+            serde_json::Value::String(s) => s.clone(),
+            serde_json::Value::Number(n) => {
+                notes.push(format!("{} converted from number to string", key));
+                n.to_string()
+            }
+            serde_json::Value::Bool(b) => {
+                notes.push(format!("{} converted from boolean to string", key));
+                b.to_string()
+            }
+            _ => {
+                notes.push(format!("{} dropped: value is not a string", key));
+                continue;
+            }
+        };
+
+        let trimmed = raw.trim();
+        if trimmed.len() != raw.len() {
+            notes.push(format!("{} had surrounding whitespace trimmed", key));
+        }
+
+        let mut value = trimmed.to_string();
+        for captures in placeholder.captures_iter(trimmed) {
+            let (token, var) = (&captures[0], &captures[1]);
+            match lookup(var).filter(|_| expand) {
+                Some(resolved) => {
+                    value = value.replace(token, &resolved);
+                    notes.push(format!("{} expanded {} from the environment", key, token));
+                }
+                None if expand => notes.push(format!(
+                    "{} references {} which is not set; left unresolved",
+                    key, token
+                )),
+                None => notes.push(format!("{} contains unresolved placeholder {}", key, token)),
+            }
+        }
+
+        normalized.insert(key.clone(), serde_json::Value::String(value));
+    }
+
+    (normalized, notes)
+}
+
+/// Imports MCP servers from Claude Desktop
+///
+/// Env values are trimmed and `${VAR}` placeholders are reported; with
+/// `expand_env` they are filled in from the current environment.
+#[tauri::command]
+pub async fn mcp_add_from_claude_desktop(
+    app: AppHandle,
+    scope: String,
+    expand_env: Option<bool>,
+) -> Result<ImportResult, String> {
+    let expand_env = expand_env.unwrap_or(false);
+    info!(
+        "Importing MCP servers from Claude Desktop with scope: {}",
+        scope
+    );
+
+    // Get Claude Desktop config path based on platform
+    let config_path = if cfg!(target_os = "macos") {
+        dirs::home_dir()
+            .ok_or_else(|| "Could not find home directory".to_string())?
+            .join("Library")
+            .join("Application Support")
+            .join("Claude")
+            .join("claude_desktop_config.json")
+    } else if cfg!(target_os = "linux") {
+        // For WSL/Linux, check common locations
+        dirs::config_dir()
+            .ok_or_else(|| "Could not find config directory".to_string())?
+            .join("Claude")
+            .join("claude_desktop_config.json")
+    } else {
+        return Err(
+            "Import from Claude Desktop is only supported on macOS and Linux/WSL".to_string(),
+        );
+    };
+
+    // Check if config file exists
+    if !config_path.exists() {
+        return Err(
+            "Claude Desktop configuration not found. Make sure Claude Desktop is installed."
+                .to_string(),
+        );
+    }
+
+    // Read and parse the config file
+    let config_content = fs::read_to_string(&config_path)
+        .map_err(|e| format!("Failed to read Claude Desktop config: {}", e))?;
+
+    let config: serde_json::Value = serde_json::from_str(&config_content)
+        .map_err(|e| format!("Failed to parse Claude Desktop config: {}", e))?;
+
+    // Extract MCP servers
+    let mcp_servers = config
+        .get("mcpServers")
+        .and_then(|v| v.as_object())
+        .ok_or_else(|| "No MCP servers found in Claude Desktop config".to_string())?;
 
     let mut imported_count = 0;
     let mut failed_count = 0;
@@ -538,6 +1433,7 @@ pub async fn mcp_add_from_claude_desktop(
                 name: name.clone(),
                 success: false,
                 error: Some("Missing command field".to_string()),
+                notes: Vec::new(),
             });
             continue;
         }
@@ -549,9 +1445,16 @@ pub async fn mcp_add_from_claude_desktop(
             json_config.insert("args".to_string(), serde_json::Value::Array(vec![]));
         }
 
-        // Add env if present
+        // Add env if present, trimming values and checking `${VAR}` placeholders
+        let mut notes = Vec::new();
         if let Some(env) = server_config.get("env").and_then(|v| v.as_object()) {
-            json_config.insert("env".to_string(), env.clone().into());
+            let (env, env_notes) =
+                normalize_env_values(env, expand_env, |var| std::env::var(var).ok());
+            for note in &env_notes {
+                warn!("{}: {}", name, note);
+            }
+            notes = env_notes;
+            json_config.insert("env".to_string(), env.into());
         } else {
             json_config.insert(
                 "env".to_string(),
@@ -572,6 +1475,7 @@ pub async fn mcp_add_from_claude_desktop(
                         name: name.clone(),
                         success: true,
                         error: None,
+                        notes,
                     });
                     info!("Successfully imported server: {}", name);
                 } else {
@@ -581,6 +1485,7 @@ pub async fn mcp_add_from_claude_desktop(
                         name: name.clone(),
                         success: false,
                         error: Some(result.message),
+                        notes,
                     });
                     error!("Failed to import server {}: {}", name, error_msg);
                 }
@@ -592,6 +1497,7 @@ pub async fn mcp_add_from_claude_desktop(
                     name: name.clone(),
                     success: false,
                     error: Some(e),
+                    notes,
                 });
                 error!("Error importing server {}: {}", name, error_msg);
             }
@@ -616,7 +1522,12 @@ pub async fn mcp_serve(app: AppHandle) -> Result<String, String> {
     info!("Starting Claude Code as MCP server");
 
     // Start the server in a separate process
-    let claude_path = match find_claude_binary(&app) {
+    let app_handle = app.clone();
+    let lookup = tokio::task::spawn_blocking(move || find_claude_binary(&app_handle))
+        .await
+        .map_err(|e| anyhow::anyhow!("Claude binary lookup task failed: {}", e))
+        .and_then(|result| result);
+    let claude_path = match lookup {
         Ok(path) => path,
         Err(e) => {
             error!("Failed to find claude binary: {}", e);
@@ -627,9 +1538,17 @@ pub async fn mcp_serve(app: AppHandle) -> Result<String, String> {
     let mut cmd = create_command_with_env(&claude_path);
     cmd.arg("mcp").arg("serve");
 
-    match cmd.spawn() {
-        Ok(_) => {
-            info!("Successfully started Claude Code MCP server");
+    match tokio::process::Command::from(cmd).spawn() {
+        Ok(child) => {
+            let pid = child.id().unwrap_or(0);
+            let registry = app.state::<crate::process::ProcessRegistryState>();
+            match registry.0.register_mcp_serve(pid, child) {
+                Ok(run_id) => info!(
+                    "Successfully started Claude Code MCP server (PID: {}, run_id: {})",
+                    pid, run_id
+                ),
+                Err(e) => error!("Failed to register MCP server process: {}", e),
+            }
             Ok("Claude Code MCP server started".to_string())
         }
         Err(e) => {
@@ -639,16 +1558,313 @@ pub async fn mcp_serve(app: AppHandle) -> Result<String, String> {
     }
 }
 
+/// Status of the `claude mcp serve` process started by the app
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MCPServeStatus {
+    pub run_id: i64,
+    pub pid: u32,
+    pub started_at: String,
+    pub uptime_secs: u64,
+    /// Number of serve processes found; more than one indicates stale instances
+    pub instances: usize,
+}
+
+/// Returns the running MCP serve session, if any
+#[tauri::command]
+pub async fn mcp_serve_status(
+    registry: tauri::State<'_, crate::process::ProcessRegistryState>,
+) -> Result<Option<MCPServeStatus>, String> {
+    let processes = registry.0.get_mcp_serve_processes()?;
+    if processes.len() > 1 {
+        warn!("Found {} running MCP serve processes", processes.len());
+    }
+
+    Ok(processes.first().map(|info| MCPServeStatus {
+        run_id: info.run_id,
+        pid: info.pid,
+        started_at: info.started_at.to_rfc3339(),
+        uptime_secs: (chrono::Utc::now() - info.started_at).num_seconds().max(0) as u64,
+        instances: processes.len(),
+    }))
+}
+
+/// Stops every running MCP serve session, returning how many were stopped
+#[tauri::command]
+pub async fn mcp_serve_stop(
+    registry: tauri::State<'_, crate::process::ProcessRegistryState>,
+) -> Result<usize, String> {
+    info!("Stopping Claude Code MCP server");
+
+    let mut stopped = 0;
+    let processes = registry.0.get_mcp_serve_processes()?;
+    // One lifecycle event for the whole shutdown instead of one per process,
+    // without holding back events from unrelated runs while the kills are awaited
+    let _batch = registry
+        .0
+        .batch_guard_for(processes.iter().map(|info| info.run_id));
+    for info in processes {
+        // kill_process unregisters the process once it is gone
+        match registry.0.kill_process(info.run_id).await {
+            Ok(true) => stopped += 1,
+            Ok(false) => warn!("MCP serve process {} was already gone", info.run_id),
+            Err(e) => error!("Failed to stop MCP serve process {}: {}", info.run_id, e),
+        }
+    }
+
+    Ok(stopped)
+}
+
+/// A `mcp serve` process found on the system that the app is not tracking
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrphanedMCPProcess {
+    pub pid: u32,
+    pub command_line: String,
+}
+
+/// A process from the system process table
+#[derive(Debug, Clone, PartialEq)]
+struct SystemProcess {
+    pid: u32,
+    parent_pid: u32,
+    command_line: String,
+}
+
+/// Parses `pid ppid command line` rows, skipping any that don't start with two numbers
+fn parse_process_list(output: &str) -> Vec<SystemProcess> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (pid, rest) = line.trim().split_once(char::is_whitespace)?;
+            let (parent_pid, command_line) = rest.trim_start().split_once(char::is_whitespace)?;
+            Some(SystemProcess {
+                pid: pid.parse().ok()?,
+                parent_pid: parent_pid.parse().ok()?,
+                command_line: command_line.trim().to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Lists every process on the system with its parent PID
+fn list_system_processes() -> Result<Vec<SystemProcess>, String> {
+    let output = if cfg!(target_os = "windows") {
+        Command::new("powershell")
+            .args([
+                "-NoProfile",
+                "-Command",
+                "Get-CimInstance Win32_Process | ForEach-Object { \"$($_.ProcessId) $($_.ParentProcessId) $($_.CommandLine)\" }",
+            ])
+            .output()
+    } else {
+        Command::new("ps").args(["-Ao", "pid=,ppid=,args="]).output()
+    }
+    .map_err(|e| format!("Failed to list processes: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to list processes: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    Ok(parse_process_list(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Whether a command line runs `claude ... mcp serve`
+///
+/// Matches on whole arguments: a program whose file name starts with `claude`,
+/// followed later by `mcp` and `serve` as consecutive arguments.
+fn is_claude_mcp_serve(command_line: &str) -> bool {
+    let args: Vec<&str> = command_line.split_whitespace().collect();
+    let Some(claude_idx) = args.iter().position(|arg| {
+        arg.trim_matches('"')
+            .rsplit(['/', '\\'])
+            .next()
+            .is_some_and(|file| file.to_lowercase().starts_with("claude"))
+    }) else {
+        return false;
+    };
+    args[claude_idx + 1..]
+        .windows(2)
+        .any(|pair| pair[0] == "mcp" && pair[1] == "serve")
+}
+
+/// Picks the `claude mcp serve` processes that nothing is managing any more
+///
+/// Skips `own_pid`, the PIDs in `tracked_pids`, and processes whose parent is still
+/// alive (other than this app or init), since their parent owns them.
+fn select_orphaned_mcp_processes(
+    processes: Vec<SystemProcess>,
+    tracked_pids: &[u32],
+    own_pid: u32,
+) -> Vec<OrphanedMCPProcess> {
+    let alive: std::collections::HashSet<u32> = processes.iter().map(|p| p.pid).collect();
+
+    processes
+        .into_iter()
+        .filter(|process| {
+            let parent_managed = process.parent_pid > 1
+                && process.parent_pid != own_pid
+                && alive.contains(&process.parent_pid);
+            process.pid != own_pid
+                && !tracked_pids.contains(&process.pid)
+                && !parent_managed
+                && is_claude_mcp_serve(&process.command_line)
+        })
+        .map(|process| OrphanedMCPProcess {
+            pid: process.pid,
+            command_line: process.command_line,
+        })
+        .collect()
+}
+
+/// Finds orphaned `claude mcp serve` processes other than the ones in `tracked_pids`
+fn find_orphaned_mcp_processes(tracked_pids: &[u32]) -> Result<Vec<OrphanedMCPProcess>, String> {
+    Ok(select_orphaned_mcp_processes(
+        list_system_processes()?,
+        tracked_pids,
+        std::process::id(),
+    ))
+}
+
+/// Terminates a single process by PID
+fn kill_pid(pid: u32) -> Result<(), String> {
+    let output = if cfg!(target_os = "windows") {
+        Command::new("taskkill")
+            .args(["/F", "/PID", &pid.to_string()])
+            .output()
+    } else {
+        Command::new("kill")
+            .args(["-TERM", &pid.to_string()])
+            .output()
+    }
+    .map_err(|e| format!("Failed to execute kill command: {}", e))?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+/// PIDs of the `mcp serve` processes the app started and still tracks
+fn tracked_mcp_serve_pids(
+    registry: &crate::process::ProcessRegistryState,
+) -> Result<Vec<u32>, String> {
+    Ok(registry
+        .0
+        .get_mcp_serve_processes()?
+        .into_iter()
+        .map(|info| info.pid)
+        .collect())
+}
+
+/// Lists `claude mcp serve` processes left running outside the app's control
+#[tauri::command]
+pub async fn list_orphaned_mcp_processes(
+    registry: tauri::State<'_, crate::process::ProcessRegistryState>,
+) -> Result<Vec<OrphanedMCPProcess>, String> {
+    let tracked = tracked_mcp_serve_pids(&registry)?;
+    tokio::task::spawn_blocking(move || find_orphaned_mcp_processes(&tracked))
+        .await
+        .map_err(|e| format!("Process listing task failed: {}", e))?
+}
+
+/// Kills orphaned `claude mcp serve` processes one by one, returning the killed PIDs
+#[tauri::command]
+pub async fn cleanup_orphaned_mcp_processes(
+    registry: tauri::State<'_, crate::process::ProcessRegistryState>,
+) -> Result<Vec<u32>, String> {
+    info!("Cleaning up orphaned MCP serve processes");
+
+    let tracked = tracked_mcp_serve_pids(&registry)?;
+    tokio::task::spawn_blocking(move || {
+        let mut killed = Vec::new();
+        for process in find_orphaned_mcp_processes(&tracked)? {
+            match kill_pid(process.pid) {
+                Ok(()) => {
+                    info!("Killed orphaned MCP serve process {}", process.pid);
+                    killed.push(process.pid);
+                }
+                Err(e) => warn!("Failed to kill MCP serve process {}: {}", process.pid, e),
+            }
+        }
+        Ok(killed)
+    })
+    .await
+    .map_err(|e| format!("Cleanup task failed: {}", e))?
+}
+
+/// Probes a single MCP server
+async fn test_server_connection(app: &AppHandle, name: &str) -> Result<(), String> {
+    // For now, we'll use the get command to test if the server exists
+    execute_claude_mcp_command(app, vec!["get", name])
+        .await
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
 /// Tests connection to an MCP server
 #[tauri::command]
 pub async fn mcp_test_connection(app: AppHandle, name: String) -> Result<String, String> {
     info!("Testing connection to MCP server: {}", name);
 
-    // For now, we'll use the get command to test if the server exists
-    match execute_claude_mcp_command(&app, vec!["get", &name]) {
-        Ok(_) => Ok(format!("Connection to {} successful", name)),
-        Err(e) => Err(e.to_string()),
+    test_server_connection(&app, &name).await?;
+    Ok(format!("Connection to {} successful", name))
+}
+
+/// Probes every configured MCP server concurrently, caching the result briefly
+#[tauri::command]
+pub async fn mcp_health_check_all(
+    app: AppHandle,
+) -> Result<HashMap<String, MCPHealthStatus>, String> {
+    let cache = app.state::<MCPHealthCache>();
+    if let Some((checked_at, results)) = cache.0.lock().map_err(|e| e.to_string())?.as_ref() {
+        if checked_at.elapsed() < HEALTH_CHECK_CACHE_TTL {
+            return Ok(results.clone());
+        }
     }
+
+    info!("Running health check for all MCP servers");
+
+    let servers = mcp_list(app.clone()).await?;
+    let deadline = tokio::time::Instant::now() + HEALTH_CHECK_DEADLINE;
+
+    let probes = servers.into_iter().map(|server| {
+        let app = app.clone();
+        async move {
+            let started = Instant::now();
+            let probe = test_server_connection(&app, &server.name);
+
+            let status = match tokio::time::timeout_at(deadline, probe).await {
+                Ok(Ok(())) => MCPHealthStatus {
+                    reachable: true,
+                    latency_ms: started.elapsed().as_millis() as u64,
+                    error: None,
+                },
+                Ok(Err(e)) => MCPHealthStatus {
+                    reachable: false,
+                    latency_ms: started.elapsed().as_millis() as u64,
+                    error: Some(e),
+                },
+                Err(_) => MCPHealthStatus {
+                    reachable: false,
+                    latency_ms: started.elapsed().as_millis() as u64,
+                    error: Some("Timed out waiting for server".to_string()),
+                },
+            };
+            (server.name, status)
+        }
+    });
+
+    let results: HashMap<String, MCPHealthStatus> = futures::future::join_all(probes)
+        .await
+        .into_iter()
+        .collect();
+
+    *cache.0.lock().map_err(|e| e.to_string())? = Some((Instant::now(), results.clone()));
+
+    Ok(results)
 }
 
 /// Resets project-scoped server approval choices
@@ -656,7 +1872,7 @@ pub async fn mcp_test_connection(app: AppHandle, name: String) -> Result<String,
 pub async fn mcp_reset_project_choices(app: AppHandle) -> Result<String, String> {
     info!("Resetting MCP project choices");
 
-    match execute_claude_mcp_command(&app, vec!["reset-project-choices"]) {
+    match execute_claude_mcp_command(&app, vec!["reset-project-choices"]).await {
         Ok(output) => {
             info!("Successfully reset MCP project choices");
             Ok(output.trim().to_string())
@@ -668,6 +1884,110 @@ pub async fn mcp_reset_project_choices(app: AppHandle) -> Result<String, String>
     }
 }
 
+/// Modification time and size of a file, or None when it doesn't exist
+fn file_fingerprint(path: &PathBuf) -> Option<(std::time::SystemTime, u64)> {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// The MCP server definitions in a watched config file
+///
+/// For `~/.claude.json` that is the user `mcpServers` plus, with `project_path`,
+/// that project's local `mcpServers`; the CLI rewrites the rest of the file
+/// constantly. For `.mcp.json` it is the file's `mcpServers`.
+fn watched_mcp_servers(path: &PathBuf, project_path: Option<&str>) -> Option<serde_json::Value> {
+    let config: serde_json::Value = serde_json::from_str(&fs::read_to_string(path).ok()?).ok()?;
+    if path.file_name().is_some_and(|name| name == ".claude.json") {
+        let local = project_path.and_then(|project_path| {
+            config
+                .get("projects")?
+                .get(project_path.trim_end_matches('/'))?
+                .get("mcpServers")
+        });
+        Some(serde_json::json!({
+            "user": config.get("mcpServers"),
+            "local": local,
+        }))
+    } else {
+        Some(config.get("mcpServers").cloned().unwrap_or_default())
+    }
+}
+
+/// Watch the Claude CLI config (`~/.claude.json`) and, with `project_path`,
+/// the project's `.mcp.json` for external edits
+///
+/// When the MCP servers in a watched file change, the health-check cache is
+/// dropped and `mcp-config-changed` is emitted with the changed path. Edits to
+/// other parts of `~/.claude.json` are ignored. Replaces any watcher already
+/// running; returns the watched paths.
+#[tauri::command]
+pub async fn mcp_watch_config(
+    app: AppHandle,
+    project_path: Option<String>,
+) -> Result<Vec<String>, String> {
+    let mut paths = vec![dirs::home_dir()
+        .ok_or_else(|| "Could not find home directory".to_string())?
+        .join(".claude.json")];
+    if let Some(project_path) = &project_path {
+        paths.push(PathBuf::from(project_path).join(".mcp.json"));
+    }
+    info!("Watching MCP config files: {:?}", paths);
+
+    let watched = paths
+        .iter()
+        .map(|p| p.to_string_lossy().to_string())
+        .collect();
+
+    let app_handle = app.clone();
+    let handle = tauri::async_runtime::spawn(async move {
+        let project_path = project_path.as_deref();
+        let mut state: Vec<_> = paths
+            .iter()
+            .map(|path| {
+                (
+                    file_fingerprint(path),
+                    watched_mcp_servers(path, project_path),
+                )
+            })
+            .collect();
+        loop {
+            tokio::time::sleep(MCP_WATCH_INTERVAL).await;
+            for (path, (fingerprint, servers)) in paths.iter().zip(state.iter_mut()) {
+                // Only parse the file when its metadata says it was written
+                let current = file_fingerprint(path);
+                if current == *fingerprint {
+                    continue;
+                }
+                let current_servers = watched_mcp_servers(path, project_path);
+                if current.is_some() && current_servers.is_none() {
+                    // Unreadable, e.g. caught mid-write; look again next tick
+                    continue;
+                }
+                *fingerprint = current;
+                if current_servers != *servers {
+                    *servers = current_servers;
+                    info!("MCP config changed: {:?}", path);
+                    app_handle.state::<MCPHealthCache>().clear();
+                    let _ = app_handle.emit("mcp-config-changed", path.to_string_lossy());
+                }
+            }
+        }
+    });
+
+    let watcher = app.state::<MCPConfigWatcher>();
+    watcher.stop();
+    *watcher.0.lock().map_err(|e| e.to_string())? = Some(handle);
+
+    Ok(watched)
+}
+
+/// Stop watching MCP config files
+#[tauri::command]
+pub async fn mcp_unwatch_config(app: AppHandle) -> Result<(), String> {
+    app.state::<MCPConfigWatcher>().stop();
+    Ok(())
+}
+
 /// Gets the status of MCP servers
 #[tauri::command]
 pub async fn mcp_get_server_status() -> Result<HashMap<String, ServerStatus>, String> {
@@ -680,10 +2000,16 @@ pub async fn mcp_get_server_status() -> Result<HashMap<String, ServerStatus>, St
 
 /// Reads .mcp.json from the current project
 #[tauri::command]
-pub async fn mcp_read_project_config(project_path: String) -> Result<MCPProjectConfig, String> {
+pub async fn mcp_read_project_config(
+    project_path: String,
+    find_project_root: Option<bool>,
+) -> Result<MCPProjectConfig, String> {
     info!("Reading .mcp.json from project: {}", project_path);
 
-    let mcp_json_path = PathBuf::from(&project_path).join(".mcp.json");
+    // Optionally resolve a subdirectory to its repository root
+    let project_dir =
+        super::claude::resolve_project_dir(&project_path, find_project_root.unwrap_or(false));
+    let mcp_json_path = project_dir.join(".mcp.json");
 
     if !mcp_json_path.exists() {
         return Ok(MCPProjectConfig {
@@ -706,6 +2032,215 @@ pub async fn mcp_read_project_config(project_path: String) -> Result<MCPProjectC
     }
 }
 
+/// A server Claude will load for a project, after merging all config scopes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MCPEffectiveServer {
+    pub name: String,
+    /// Scope the active definition comes from: "local", "project" or "user"
+    pub scope: String,
+    pub config: MCPServerConfig,
+    /// Lower-precedence scopes that also define this server and are ignored
+    pub shadowed_scopes: Vec<String>,
+}
+
+/// Reads the Claude CLI config (`~/.claude.json`), treating a missing file as empty
+pub(crate) fn read_claude_json() -> Result<serde_json::Value, String> {
+    let claude_json_path = dirs::home_dir()
+        .ok_or("Could not find home directory")?
+        .join(".claude.json");
+    match fs::read_to_string(&claude_json_path) {
+        Ok(content) => serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse ~/.claude.json: {}", e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(serde_json::Value::Null),
+        Err(e) => Err(format!("Failed to read ~/.claude.json: {}", e)),
+    }
+}
+
+/// Parses an `mcpServers` object, skipping entries that aren't valid server configs
+pub(crate) fn parse_server_map(
+    value: Option<&serde_json::Value>,
+    scope: &str,
+) -> HashMap<String, MCPServerConfig> {
+    let Some(servers) = value.and_then(|v| v.as_object()) else {
+        return HashMap::new();
+    };
+
+    servers
+        .iter()
+        .filter_map(|(name, config)| {
+            match serde_json::from_value::<MCPServerConfig>(config.clone()) {
+                Ok(config) => Some((name.clone(), config)),
+                Err(e) => {
+                    warn!("Skipping invalid {} MCP server '{}': {}", scope, name, e);
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+/// Merges scoped server maps, given highest precedence first
+///
+/// The first scope defining a name wins; later scopes defining it are reported
+/// as shadowed. Results are sorted by name.
+fn merge_effective_servers(
+    layers: Vec<(&str, HashMap<String, MCPServerConfig>)>,
+) -> Vec<MCPEffectiveServer> {
+    let mut merged: HashMap<String, MCPEffectiveServer> = HashMap::new();
+    for (scope, servers) in layers {
+        for (name, config) in servers {
+            match merged.get_mut(&name) {
+                Some(existing) => existing.shadowed_scopes.push(scope.to_string()),
+                None => {
+                    merged.insert(
+                        name.clone(),
+                        MCPEffectiveServer {
+                            name,
+                            scope: scope.to_string(),
+                            config,
+                            shadowed_scopes: Vec::new(),
+                        },
+                    );
+                }
+            }
+        }
+    }
+
+    let mut servers: Vec<MCPEffectiveServer> = merged.into_values().collect();
+    servers.sort_by(|a, b| a.name.cmp(&b.name));
+    servers
+}
+
+/// Lists the MCP servers Claude will load for a project
+///
+/// Combines user servers and project-local servers from `~/.claude.json` with the
+/// project's `.mcp.json`. On name collisions local wins over project, and project
+/// wins over user; overridden definitions are listed in `shadowed_scopes`.
+#[tauri::command]
+pub async fn mcp_effective_servers(
+    project_path: String,
+) -> Result<Vec<MCPEffectiveServer>, String> {
+    info!("Resolving effective MCP servers for: {}", project_path);
+
+    let project_servers = mcp_read_project_config(project_path.clone(), None)
+        .await?
+        .mcp_servers;
+
+    let claude_json = read_claude_json()?;
+    let user_servers = parse_server_map(claude_json.get("mcpServers"), "user");
+    let local_servers = parse_server_map(
+        claude_json
+            .get("projects")
+            .and_then(|projects| projects.get(project_path.trim_end_matches('/')))
+            .and_then(|project| project.get("mcpServers")),
+        "local",
+    );
+
+    Ok(merge_effective_servers(vec![
+        ("local", local_servers),
+        ("project", project_servers),
+        ("user", user_servers),
+    ]))
+}
+
+/// Checks that a server config has the fields required by its transport
+fn validate_server_config(name: &str, config: &MCPServerConfig) -> MCPServerValidation {
+    let transport = config.transport.as_deref().unwrap_or("stdio").to_string();
+    let mut errors = Vec::new();
+    let mut warnings = Vec::new();
+
+    if name.trim().is_empty() {
+        errors.push("Server name must not be empty".to_string());
+    }
+
+    match transport.as_str() {
+        "stdio" => {
+            if config
+                .command
+                .as_deref()
+                .is_none_or(|c| c.trim().is_empty())
+            {
+                errors.push("stdio servers require a non-empty \"command\"".to_string());
+            }
+            if config.url.is_some() {
+                warnings.push("\"url\" is ignored for stdio servers".to_string());
+            }
+            if !config.headers.is_empty() {
+                warnings.push("\"headers\" are ignored for stdio servers".to_string());
+            }
+        }
+        "sse" | "http" => {
+            match config.url.as_deref().map(str::trim) {
+                None | Some("") => {
+                    errors.push(format!("{} servers require a \"url\"", transport));
+                }
+                Some(url) if !url.starts_with("http://") && !url.starts_with("https://") => {
+                    errors.push(format!("\"url\" must be an http(s) URL, got: {}", url));
+                }
+                Some(_) => {}
+            }
+            if config.command.is_some() || !config.args.is_empty() {
+                warnings.push(format!(
+                    "\"command\" and \"args\" are ignored for {} servers",
+                    transport
+                ));
+            }
+        }
+        other => {
+            errors.push(format!(
+                "Unknown transport type \"{}\" (expected stdio, sse or http)",
+                other
+            ));
+        }
+    }
+
+    for key in config.env.keys() {
+        if key.is_empty() || key.contains('=') {
+            errors.push(format!("Invalid environment variable name: {:?}", key));
+        }
+    }
+
+    MCPServerValidation {
+        name: name.to_string(),
+        transport,
+        valid: errors.is_empty(),
+        errors,
+        warnings,
+    }
+}
+
+/// Validates the contents of a .mcp.json file without writing anything to disk
+#[tauri::command]
+pub async fn mcp_validate_project_config(
+    json: String,
+) -> Result<MCPConfigValidationReport, String> {
+    info!("Validating .mcp.json content");
+
+    let config = match serde_json::from_str::<MCPProjectConfig>(&json) {
+        Ok(config) => config,
+        Err(e) => {
+            return Ok(MCPConfigValidationReport {
+                valid: false,
+                parse_error: Some(e.to_string()),
+                servers: Vec::new(),
+            });
+        }
+    };
+
+    let mut servers: Vec<MCPServerValidation> = config
+        .mcp_servers
+        .iter()
+        .map(|(name, server)| validate_server_config(name, server))
+        .collect();
+    servers.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(MCPConfigValidationReport {
+        valid: servers.iter().all(|s| s.valid),
+        parse_error: None,
+        servers,
+    })
+}
+
 /// Saves .mcp.json to the current project
 #[tauri::command]
 pub async fn mcp_save_project_config(
@@ -724,3 +2259,357 @@ pub async fn mcp_save_project_config(
 
     Ok("Project MCP configuration saved".to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_env_values() {
+        let env = serde_json::json!({
+            "TOKEN": "  abc  ",
+            "HOME_DIR": "${HOME}/data",
+            "MISSING": "${NOPE}",
+            "PORT": 8080,
+            "NESTED": {"a": 1}
+        });
+        let env = env.as_object().unwrap();
+        let lookup = |var: &str| (var == "HOME").then(|| "/home/me".to_string());
+
+        let (kept, notes) = normalize_env_values(env, false, lookup);
+        assert_eq!(kept["TOKEN"], "abc");
+        assert_eq!(kept["HOME_DIR"], "${HOME}/data");
+        assert_eq!(kept["PORT"], "8080");
+        assert!(!kept.contains_key("NESTED"));
+        assert!(notes
+            .iter()
+            .any(|n| n.contains("unresolved placeholder ${NOPE}")));
+
+        let (expanded, notes) = normalize_env_values(env, true, lookup);
+        assert_eq!(expanded["HOME_DIR"], "/home/me/data");
+        assert_eq!(expanded["MISSING"], "${NOPE}");
+        assert!(notes.iter().any(|n| n.contains("${NOPE} which is not set")));
+    }
+
+    #[test]
+    fn test_merge_effective_servers_precedence() {
+        let config = |command: &str| MCPServerConfig {
+            transport: None,
+            command: Some(command.to_string()),
+            args: vec![],
+            env: HashMap::new(),
+            url: None,
+            headers: HashMap::new(),
+        };
+        let user = HashMap::from([
+            ("shared".to_string(), config("user-shared")),
+            ("only-user".to_string(), config("user-only")),
+        ]);
+        let project = HashMap::from([("shared".to_string(), config("project-shared"))]);
+
+        let servers = merge_effective_servers(vec![
+            ("local", HashMap::new()),
+            ("project", project),
+            ("user", user),
+        ]);
+
+        assert_eq!(servers.len(), 2);
+        assert_eq!(servers[0].name, "only-user");
+        assert_eq!(servers[0].scope, "user");
+        assert!(servers[0].shadowed_scopes.is_empty());
+        assert_eq!(servers[1].name, "shared");
+        assert_eq!(servers[1].scope, "project");
+        assert_eq!(servers[1].config.command.as_deref(), Some("project-shared"));
+        assert_eq!(servers[1].shadowed_scopes, vec!["user"]);
+
+        let parsed = parse_server_map(
+            Some(&serde_json::json!({"ok": {"command": "npx"}, "bad": {"args": "nope"}})),
+            "user",
+        );
+        assert_eq!(parsed.len(), 1);
+        assert!(parsed.contains_key("ok"));
+    }
+
+    #[test]
+    fn test_parse_mcp_install_link() {
+        use base64::Engine as _;
+
+        let config = r#"{"command":"npx","args":["-y","server"]}"#;
+        let encoded = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(config);
+        let (name, parsed) =
+            parse_mcp_install_link(&format!("mcp://install?name=fs&config={}", encoded)).unwrap();
+        assert_eq!(name, "fs");
+        assert_eq!(parsed.command.as_deref(), Some("npx"));
+        assert_eq!(parsed.args, vec!["-y", "server"]);
+
+        let link = "https://example.com/install?config=%7B%22name%22%3A%22remote%22%2C%22type%22%3A%22sse%22%2C%22url%22%3A%22https%3A%2F%2Fexample.com%2Fsse%22%7D";
+        let (name, parsed) = parse_mcp_install_link(link).unwrap();
+        assert_eq!(name, "remote");
+        assert_eq!(parsed.transport.as_deref(), Some("sse"));
+
+        assert!(parse_mcp_install_link("file:///tmp/x?config={}").is_err());
+        assert!(parse_mcp_install_link("mcp://install?name=x").is_err());
+    }
+
+    #[test]
+    fn test_suspicious_link_env() {
+        let mut config: MCPServerConfig =
+            serde_json::from_str(r#"{"command":"npx","env":{"API_KEY":"abc"}}"#).unwrap();
+        assert!(suspicious_link_env(&config).is_empty());
+
+        config
+            .env
+            .insert("LD_PRELOAD".to_string(), "lib.so".to_string());
+        config
+            .env
+            .insert("DATA_DIR".to_string(), "/etc".to_string());
+        assert_eq!(suspicious_link_env(&config), vec!["DATA_DIR", "LD_PRELOAD"]);
+    }
+
+    #[test]
+    fn test_parse_mcp_list_json() {
+        let list = r#"[{"name":"fs","type":"stdio","command":"npx","args":["-y","fs"],"scope":"user"},
+                       {"name":"remote","url":"https://example.com/sse"}]"#;
+        let servers = parse_mcp_list_json(list).unwrap();
+        assert_eq!(servers.len(), 2);
+        assert_eq!(servers[0].name, "fs");
+        assert_eq!(servers[0].args, vec!["-y", "fs"]);
+        assert_eq!(servers[0].scope, "user");
+        assert_eq!(servers[1].transport, "sse");
+        assert_eq!(servers[1].scope, "local");
+
+        let map = r#"{"mcpServers":{"db":{"type":"http","url":"http://localhost:9000"}}}"#;
+        let servers = parse_mcp_list_json(map).unwrap();
+        assert_eq!(servers[0].name, "db");
+        assert_eq!(servers[0].transport, "http");
+
+        assert!(parse_mcp_list_json("fs: npx -y fs").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_retry_with_backoff_gives_up_after_max_attempts() {
+        let config = McpRetryConfig {
+            max_attempts: 3,
+            delay: Duration::from_millis(1),
+        };
+        let mut calls = 0;
+
+        let result: Result<()> = retry_with_backoff(config, || {
+            calls += 1;
+            async { Err(anyhow::anyhow!("binary not found")) }
+        })
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(calls, 3);
+    }
+
+    #[tokio::test]
+    async fn test_retry_with_backoff_stops_on_success() {
+        let config = McpRetryConfig {
+            max_attempts: 5,
+            delay: Duration::from_millis(1),
+        };
+        let mut calls = 0;
+
+        let result = retry_with_backoff(config, || {
+            calls += 1;
+            let attempt = calls;
+            async move {
+                if attempt < 2 {
+                    Err(anyhow::anyhow!("binary not found"))
+                } else {
+                    Ok(attempt)
+                }
+            }
+        })
+        .await;
+
+        assert_eq!(result.unwrap(), 2);
+        assert_eq!(calls, 2);
+    }
+
+    #[test]
+    fn test_configured_servers_keeps_env_and_scopes() {
+        let project = tempfile::TempDir::new().unwrap();
+        fs::write(
+            project.path().join(".mcp.json"),
+            r#"{"mcpServers": {"docs": {"type": "http", "url": "https://docs.example.com/mcp"}}}"#,
+        )
+        .unwrap();
+        let project_key = project.path().to_string_lossy().to_string();
+        let claude_json = serde_json::json!({
+            "mcpServers": {
+                "github": {"command": "npx", "args": ["gh"], "env": {"GITHUB_TOKEN": "ghp_x"}}
+            },
+            "projects": {
+                project_key: {"mcpServers": {"db": {"command": "db-mcp"}}}
+            }
+        });
+
+        let servers = configured_servers(&claude_json, project.path());
+        let scopes: Vec<(&str, &str)> = servers
+            .iter()
+            .map(|s| (s.name.as_str(), s.scope.as_str()))
+            .collect();
+        assert_eq!(
+            scopes,
+            vec![("db", "local"), ("docs", "project"), ("github", "user")]
+        );
+        assert_eq!(
+            servers[2]
+                .config
+                .env
+                .get("GITHUB_TOKEN")
+                .map(String::as_str),
+            Some("ghp_x")
+        );
+    }
+
+    #[test]
+    fn test_parse_process_list() {
+        let output =
+            "    1     0 /sbin/init\n  420     1 claude mcp serve\n  bogus line\n  77   420\n";
+        assert_eq!(
+            parse_process_list(output),
+            vec![
+                SystemProcess {
+                    pid: 1,
+                    parent_pid: 0,
+                    command_line: "/sbin/init".to_string(),
+                },
+                SystemProcess {
+                    pid: 420,
+                    parent_pid: 1,
+                    command_line: "claude mcp serve".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_is_claude_mcp_serve() {
+        assert!(is_claude_mcp_serve("claude mcp serve"));
+        assert!(is_claude_mcp_serve(
+            "/usr/local/bin/claude mcp serve --debug"
+        ));
+        assert!(is_claude_mcp_serve(
+            "node /home/u/.npm/bin/claude.js mcp serve"
+        ));
+        assert!(is_claude_mcp_serve(
+            "\"C:\\Program Files\\Claude\\claude.exe\" mcp serve"
+        ));
+        assert!(!is_claude_mcp_serve("claude mcp list"));
+        assert!(!is_claude_mcp_serve("vim notes-about-claude-mcp serve.md"));
+        assert!(!is_claude_mcp_serve("grep mcp serve claude.log"));
+    }
+
+    #[test]
+    fn test_select_orphaned_mcp_processes() {
+        let process = |pid, parent_pid, command_line: &str| SystemProcess {
+            pid,
+            parent_pid,
+            command_line: command_line.to_string(),
+        };
+        let own_pid = 100;
+        let processes = vec![
+            process(1, 0, "/sbin/init"),
+            process(50, 1, "bash"),
+            // Reparented to init: orphaned
+            process(200, 1, "claude mcp serve"),
+            // Parent died without reparenting (Windows): orphaned
+            process(201, 999, "claude mcp serve"),
+            // Started by a live shell: owned by it
+            process(202, 50, "claude mcp serve"),
+            // Our own child but untracked: orphaned
+            process(203, own_pid, "claude mcp serve"),
+            // Tracked by the registry
+            process(204, own_pid, "claude mcp serve"),
+            process(205, 1, "claude mcp list"),
+        ];
+
+        let pids: Vec<u32> = select_orphaned_mcp_processes(processes, &[204], own_pid)
+            .into_iter()
+            .map(|p| p.pid)
+            .collect();
+        assert_eq!(pids, vec![200, 201, 203]);
+    }
+
+    fn validate_json(name: &str, json: serde_json::Value) -> MCPServerValidation {
+        let config: MCPServerConfig = serde_json::from_value(json).unwrap();
+        validate_server_config(name, &config)
+    }
+
+    #[test]
+    fn test_validate_server_config_stdio() {
+        let ok = validate_json(
+            "fs",
+            serde_json::json!({ "command": "npx", "args": ["fs"] }),
+        );
+        assert!(ok.valid);
+        assert_eq!(ok.transport, "stdio");
+        assert!(ok.warnings.is_empty());
+
+        let missing = validate_json(
+            "fs",
+            serde_json::json!({ "type": "stdio", "command": "  " }),
+        );
+        assert!(!missing.valid);
+        assert_eq!(missing.errors.len(), 1);
+
+        let extra = validate_json(
+            "fs",
+            serde_json::json!({
+                "command": "npx",
+                "url": "https://example.com",
+                "headers": { "X-Key": "1" }
+            }),
+        );
+        assert!(extra.valid);
+        assert_eq!(extra.warnings.len(), 2);
+    }
+
+    #[test]
+    fn test_validate_server_config_remote() {
+        let ok = validate_json(
+            "api",
+            serde_json::json!({ "type": "http", "url": "https://example.com/mcp" }),
+        );
+        assert!(ok.valid);
+
+        let missing = validate_json("api", serde_json::json!({ "type": "sse", "url": " " }));
+        assert!(!missing.valid);
+
+        let bad_scheme = validate_json(
+            "api",
+            serde_json::json!({ "type": "http", "url": "ftp://example.com" }),
+        );
+        assert!(!bad_scheme.valid);
+
+        let extra = validate_json(
+            "api",
+            serde_json::json!({ "type": "sse", "url": "http://localhost:3000", "command": "x" }),
+        );
+        assert!(extra.valid);
+        assert_eq!(extra.warnings.len(), 1);
+    }
+
+    #[test]
+    fn test_validate_server_config_rejects_bad_names_and_env() {
+        let unknown = validate_json(
+            "ws",
+            serde_json::json!({ "type": "websocket", "url": "ws://x" }),
+        );
+        assert!(!unknown.valid);
+        assert_eq!(unknown.transport, "websocket");
+
+        let blank_name = validate_json(" ", serde_json::json!({ "command": "npx" }));
+        assert!(!blank_name.valid);
+
+        let bad_env = validate_json(
+            "fs",
+            serde_json::json!({ "command": "npx", "env": { "A=B": "1", "": "2", "OK": "3" } }),
+        );
+        assert_eq!(bad_env.errors.len(), 2);
+    }
+}
@@ -2,6 +2,7 @@ pub mod agents;
 pub mod claude;
 pub mod mcp;
 pub mod proxy;
+pub mod settings_profile;
 pub mod slash_commands;
 pub mod storage;
 pub mod usage;
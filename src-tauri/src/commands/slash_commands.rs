@@ -4,6 +4,7 @@ use log::{debug, error, info};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, Manager};
 
 /// Represents a custom slash command
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,12 +27,321 @@ pub struct SlashCommand {
     pub description: Option<String>,
     /// Allowed tools from frontmatter
     pub allowed_tools: Vec<String>,
+    /// Disallowed tools from frontmatter
+    pub disallowed_tools: Vec<String>,
+    /// Model declared in frontmatter, used unless the caller explicitly picks one
+    pub model: Option<String>,
     /// Whether the command has bash commands (!)
     pub has_bash_commands: bool,
     /// Whether the command has file references (@)
     pub has_file_references: bool,
     /// Whether the command uses $ARGUMENTS placeholder
     pub accepts_arguments: bool,
+    /// Creation time (RFC 3339), from frontmatter or file metadata
+    pub created_at: Option<String>,
+    /// Last modification time (RFC 3339), from frontmatter or file metadata
+    pub modified_at: Option<String>,
+    /// Tags for grouping, from frontmatter `tags:` or the metadata sidecar
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// A command as carried in an import/export bundle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedCommand {
+    /// Command name (without prefix)
+    pub name: String,
+    /// Optional namespace (e.g., "frontend" or "frontend:components")
+    pub namespace: Option<String>,
+    /// Full markdown content, including any frontmatter
+    pub content: String,
+    /// Scope the command was exported from: "user" (default) or "project"
+    #[serde(default)]
+    pub scope: Option<String>,
+}
+
+/// Outcome of importing a set of commands
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CommandImportResult {
+    /// Commands that were newly created
+    pub imported: Vec<String>,
+    /// Existing commands that were replaced
+    pub overwritten: Vec<String>,
+    /// Existing commands left untouched because overwrite was disabled
+    pub conflicts: Vec<String>,
+    /// Commands that could not be imported, with the reason
+    pub errors: Vec<String>,
+}
+
+/// Error returned by the slash command Tauri commands
+///
+/// Serializes as `{ "code": "not_found", "message": "..." }` so the frontend
+/// can branch on `code` instead of matching message text.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "code", content = "message", rename_all = "snake_case")]
+pub enum CommandError {
+    /// No command with the given ID or name
+    NotFound(String),
+    /// A command with the given name already exists
+    AlreadyExists(String),
+    /// Command name or namespace is not usable as a file name
+    InvalidName(String),
+    /// Other invalid arguments (scope, missing project path, malformed ID)
+    InvalidInput(String),
+    /// Commands expected to be identical differ
+    ContentMismatch(String),
+    /// Reading or writing command files failed
+    Io(String),
+    /// Anything else
+    Internal(String),
+}
+
+impl CommandError {
+    /// Stable machine-readable code, matching the serialized `code` field
+    pub fn code(&self) -> &'static str {
+        match self {
+            CommandError::NotFound(_) => "not_found",
+            CommandError::AlreadyExists(_) => "already_exists",
+            CommandError::InvalidName(_) => "invalid_name",
+            CommandError::InvalidInput(_) => "invalid_input",
+            CommandError::ContentMismatch(_) => "content_mismatch",
+            CommandError::Io(_) => "io",
+            CommandError::Internal(_) => "internal",
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            CommandError::NotFound(m)
+            | CommandError::AlreadyExists(m)
+            | CommandError::InvalidName(m)
+            | CommandError::InvalidInput(m)
+            | CommandError::ContentMismatch(m)
+            | CommandError::Io(m)
+            | CommandError::Internal(m) => m,
+        }
+    }
+}
+
+impl std::fmt::Display for CommandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl From<String> for CommandError {
+    fn from(message: String) -> Self {
+        CommandError::Internal(message)
+    }
+}
+
+impl From<CommandError> for String {
+    fn from(error: CommandError) -> Self {
+        error.to_string()
+    }
+}
+
+/// Progress of a running command import, emitted after each command
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandImportProgress {
+    /// Commands handled so far
+    pub processed: usize,
+    /// Total commands in this import
+    pub total: usize,
+    /// Display name of the command just handled
+    pub current_name: String,
+}
+
+/// Ids of running imports, each with whether the UI has asked to cancel it
+static RUNNING_IMPORTS: std::sync::Mutex<Vec<(String, bool)>> = std::sync::Mutex::new(Vec::new());
+
+/// Reports progress for an import and checks whether it has been cancelled
+///
+/// Without an `import_id` no events are emitted and the import cannot be cancelled.
+/// The import counts as running until this is dropped.
+struct ImportProgress<'a> {
+    app: &'a AppHandle,
+    import_id: Option<&'a str>,
+    processed: usize,
+    total: usize,
+}
+
+impl<'a> ImportProgress<'a> {
+    fn new(app: &'a AppHandle, import_id: Option<&'a str>, total: usize) -> Self {
+        if let Some(id) = import_id {
+            if let Ok(mut running) = RUNNING_IMPORTS.lock() {
+                running.retain(|(running_id, _)| running_id != id);
+                running.push((id.to_string(), false));
+            }
+        }
+        Self {
+            app,
+            import_id,
+            processed: 0,
+            total,
+        }
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.import_id.is_some_and(|id| {
+            RUNNING_IMPORTS
+                .lock()
+                .map(|running| {
+                    running
+                        .iter()
+                        .any(|(running_id, cancelled)| running_id == id && *cancelled)
+                })
+                .unwrap_or(false)
+        })
+    }
+
+    fn advance(&mut self, current_name: &str) {
+        self.processed += 1;
+        if let Some(id) = self.import_id {
+            let _ = self.app.emit(
+                &format!("command-import-progress:{}", id),
+                CommandImportProgress {
+                    processed: self.processed,
+                    total: self.total,
+                    current_name: current_name.to_string(),
+                },
+            );
+        }
+    }
+}
+
+/// Unregister the import once it has finished, however it ended
+impl Drop for ImportProgress<'_> {
+    fn drop(&mut self) {
+        if let Some(id) = self.import_id {
+            if let Ok(mut running) = RUNNING_IMPORTS.lock() {
+                running.retain(|(running_id, _)| running_id != id);
+            }
+        }
+    }
+}
+
+/// Setting key for a user commands directory that replaces `~/.claude/commands`
+pub const COMMANDS_DIR_OVERRIDE_KEY: &str = "commands_dir_override";
+
+/// Validated override loaded from settings; `None` means the default location
+static COMMANDS_DIR_OVERRIDE: std::sync::Mutex<Option<PathBuf>> = std::sync::Mutex::new(None);
+
+/// Resolve the user commands directory, creating an override directory if needed
+///
+/// An empty or missing override falls back to `~/.claude/commands`. Overrides must be
+/// absolute (a leading `~/` is expanded) and must be, or be creatable as, a directory.
+pub fn resolve_commands_dir(override_dir: Option<&str>) -> Result<PathBuf, String> {
+    let override_dir = override_dir.map(str::trim).filter(|dir| !dir.is_empty());
+    let Some(dir) = override_dir else {
+        return Ok(dirs::home_dir()
+            .ok_or_else(|| "Could not find home directory".to_string())?
+            .join(".claude")
+            .join("commands"));
+    };
+
+    let path = match dir.strip_prefix("~/") {
+        Some(rest) => dirs::home_dir()
+            .ok_or_else(|| "Could not find home directory".to_string())?
+            .join(rest),
+        None => PathBuf::from(dir),
+    };
+    if !path.is_absolute() {
+        return Err(format!(
+            "Commands directory must be an absolute path: {}",
+            dir
+        ));
+    }
+
+    fs::create_dir_all(&path)
+        .map_err(|e| format!("Failed to create commands directory {}: {}", dir, e))?;
+    if !path.is_dir() {
+        return Err(format!("Commands directory is not a directory: {}", dir));
+    }
+
+    Ok(path)
+}
+
+/// The user commands directory, honouring the `commands_dir_override` setting
+pub fn user_commands_dir() -> Result<PathBuf, String> {
+    let override_dir = COMMANDS_DIR_OVERRIDE
+        .lock()
+        .map_err(|e| e.to_string())?
+        .clone();
+    match override_dir {
+        Some(dir) => Ok(dir),
+        None => resolve_commands_dir(None),
+    }
+}
+
+/// Load the commands directory override from settings, falling back to the default
+/// location when it is unset or invalid
+pub fn load_commands_dir_override(conn: &rusqlite::Connection) {
+    let configured = super::agents::read_setting(conn, COMMANDS_DIR_OVERRIDE_KEY)
+        .ok()
+        .flatten()
+        .filter(|dir| !dir.trim().is_empty());
+
+    let resolved = configured.and_then(|dir| match resolve_commands_dir(Some(&dir)) {
+        Ok(path) => {
+            info!("Using commands directory override: {:?}", path);
+            Some(path)
+        }
+        Err(e) => {
+            error!("Ignoring commands directory override: {}", e);
+            None
+        }
+    });
+
+    if let Ok(mut current) = COMMANDS_DIR_OVERRIDE.lock() {
+        *current = resolved;
+    }
+}
+
+/// Get the configured commands directory override, if any
+#[tauri::command]
+pub async fn get_commands_dir_override(
+    db: tauri::State<'_, super::agents::AgentDb>,
+) -> Result<Option<String>, CommandError> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    Ok(
+        super::agents::read_setting(&conn, COMMANDS_DIR_OVERRIDE_KEY)?
+            .filter(|dir| !dir.trim().is_empty()),
+    )
+}
+
+/// Set or clear (with `None` or an empty string) the user commands directory override
+///
+/// Returns the commands directory in effect afterwards.
+#[tauri::command]
+pub async fn set_commands_dir_override(
+    db: tauri::State<'_, super::agents::AgentDb>,
+    path: Option<String>,
+) -> Result<String, CommandError> {
+    let path = path.map(|p| p.trim().to_string()).filter(|p| !p.is_empty());
+    let resolved = match &path {
+        Some(dir) => Some(resolve_commands_dir(Some(dir)).map_err(CommandError::InvalidInput)?),
+        None => None,
+    };
+
+    {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        super::agents::write_setting(
+            &conn,
+            COMMANDS_DIR_OVERRIDE_KEY,
+            path.as_deref().unwrap_or(""),
+        )?;
+    }
+
+    let effective = match resolved {
+        Some(dir) => dir,
+        None => resolve_commands_dir(None)?,
+    };
+    let mut current = COMMANDS_DIR_OVERRIDE.lock().map_err(|e| e.to_string())?;
+    *current = path.map(|_| effective.clone());
+
+    info!("Commands directory set to {:?}", effective);
+    Ok(effective.to_string_lossy().to_string())
 }
 
 /// YAML frontmatter structure
@@ -39,7 +349,78 @@ pub struct SlashCommand {
 struct CommandFrontmatter {
     #[serde(rename = "allowed-tools")]
     allowed_tools: Option<Vec<String>>,
+    #[serde(rename = "disallowed-tools")]
+    disallowed_tools: Option<Vec<String>>,
+    description: Option<String>,
+    model: Option<String>,
+    created_at: Option<String>,
+    modified_at: Option<String>,
+    tags: Option<Vec<String>>,
+}
+
+/// Command metadata kept in a `<name>.md.meta.json` sidecar instead of frontmatter
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CommandMetadataSidecar {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    allowed_tools: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    disallowed_tools: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    tags: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    created_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    modified_at: Option<String>,
+}
+
+/// Path of the metadata sidecar for a command file
+fn command_sidecar_path(file_path: &Path) -> PathBuf {
+    let mut name = file_path.file_name().unwrap_or_default().to_os_string();
+    name.push(".meta.json");
+    file_path.with_file_name(name)
+}
+
+/// Read a command's metadata sidecar, if present
+///
+/// A malformed sidecar is logged and ignored so the command still loads.
+fn read_command_sidecar(file_path: &Path) -> Option<CommandMetadataSidecar> {
+    let sidecar_path = command_sidecar_path(file_path);
+    let content = fs::read_to_string(&sidecar_path).ok()?;
+    serde_json::from_str(&content)
+        .map_err(|e| debug!("Ignoring invalid sidecar {:?}: {}", sidecar_path, e))
+        .ok()
+}
+
+/// Fill frontmatter gaps from the sidecar; values present in frontmatter win
+fn merge_command_sidecar(
+    frontmatter: Option<CommandFrontmatter>,
+    sidecar: Option<CommandMetadataSidecar>,
+) -> Option<CommandFrontmatter> {
+    let Some(sidecar) = sidecar else {
+        return frontmatter;
+    };
+    let fm = frontmatter.unwrap_or(CommandFrontmatter {
+        allowed_tools: None,
+        disallowed_tools: None,
+        description: None,
+        model: None,
+        created_at: None,
+        modified_at: None,
+        tags: None,
+    });
+    Some(CommandFrontmatter {
+        allowed_tools: fm.allowed_tools.or(sidecar.allowed_tools),
+        disallowed_tools: fm.disallowed_tools.or(sidecar.disallowed_tools),
+        description: fm.description.or(sidecar.description),
+        model: fm.model.or(sidecar.model),
+        created_at: fm.created_at.or(sidecar.created_at),
+        modified_at: fm.modified_at.or(sidecar.modified_at),
+        tags: fm.tags.or(sidecar.tags),
+    })
 }
 
 /// Parse a markdown file with optional YAML frontmatter
@@ -81,6 +462,62 @@ fn parse_markdown_with_frontmatter(content: &str) -> Result<(Option<CommandFront
     }
 }
 
+/// Quote a frontmatter value so characters like `: ` or `#` can't change its meaning
+///
+/// JSON string literals are valid double-quoted YAML scalars.
+fn yaml_quote(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_else(|_| "\"\"".to_string())
+}
+
+/// Tags currently recorded for a command, from its frontmatter or sidecar
+fn existing_command_tags(file_path: &Path) -> Option<Vec<String>> {
+    let frontmatter = fs::read_to_string(file_path)
+        .ok()
+        .and_then(|existing| parse_markdown_with_frontmatter(&existing).ok())
+        .and_then(|(fm, _)| fm);
+    merge_command_sidecar(frontmatter, read_command_sidecar(file_path)).and_then(|fm| fm.tags)
+}
+
+/// Format a filesystem timestamp as RFC 3339
+fn format_system_time(time: std::time::SystemTime) -> String {
+    chrono::DateTime::<chrono::Utc>::from(time).to_rfc3339()
+}
+
+/// Resolve created/modified timestamps, preferring frontmatter values since
+/// they survive copies. Creation time is not available on every filesystem
+/// (`created()` returns `Unsupported`), so it falls back to the modification
+/// time and finally to now, ensuring the command still loads.
+fn resolve_command_timestamps(
+    file_path: &Path,
+    frontmatter: Option<&CommandFrontmatter>,
+) -> (String, String) {
+    let metadata = fs::metadata(file_path).ok();
+    let fs_modified = metadata
+        .as_ref()
+        .and_then(|m| m.modified().ok())
+        .map(format_system_time);
+    let fs_created = metadata
+        .as_ref()
+        .and_then(|m| match m.created() {
+            Ok(created) => Some(created),
+            Err(e) => {
+                debug!("Creation time unavailable for {:?}: {}", file_path, e);
+                None
+            }
+        })
+        .map(format_system_time)
+        .or_else(|| fs_modified.clone());
+
+    let fm_created = frontmatter.and_then(|fm| fm.created_at.clone());
+    let fm_modified = frontmatter.and_then(|fm| fm.modified_at.clone());
+    let now = || chrono::Utc::now().to_rfc3339();
+
+    (
+        fm_created.or(fs_created).unwrap_or_else(now),
+        fm_modified.or(fs_modified).unwrap_or_else(now),
+    )
+}
+
 /// Extract command name and namespace from file path
 fn extract_command_info(file_path: &Path, base_path: &Path) -> Result<(String, Option<String>)> {
     let relative_path = file_path
@@ -118,8 +555,9 @@ fn load_command_from_file(file_path: &Path, base_path: &Path, scope: &str) -> Re
     // Read file content
     let content = fs::read_to_string(file_path).context("Failed to read command file")?;
 
-    // Parse frontmatter
+    // Parse frontmatter, filling gaps from a metadata sidecar if there is one
     let (frontmatter, body) = parse_markdown_with_frontmatter(&content)?;
+    let frontmatter = merge_command_sidecar(frontmatter, read_command_sidecar(file_path));
 
     // Extract command info
     let (name, namespace) = extract_command_info(file_path, base_path)?;
@@ -142,11 +580,20 @@ fn load_command_from_file(file_path: &Path, base_path: &Path, scope: &str) -> Re
     let has_file_references = body.contains('@');
     let accepts_arguments = body.contains("$ARGUMENTS");
 
+    let (created_at, modified_at) = resolve_command_timestamps(file_path, frontmatter.as_ref());
+
     // Extract metadata from frontmatter
-    let (description, allowed_tools) = if let Some(fm) = frontmatter {
-        (fm.description, fm.allowed_tools.unwrap_or_default())
+    let (description, allowed_tools, disallowed_tools, model, tags) = if let Some(fm) = frontmatter
+    {
+        (
+            fm.description,
+            fm.allowed_tools.unwrap_or_default(),
+            fm.disallowed_tools.unwrap_or_default(),
+            fm.model.filter(|m| !m.trim().is_empty()),
+            fm.tags.unwrap_or_default(),
+        )
     } else {
-        (None, Vec::new())
+        (None, Vec::new(), Vec::new(), None, Vec::new())
     };
 
     Ok(SlashCommand {
@@ -159,9 +606,14 @@ fn load_command_from_file(file_path: &Path, base_path: &Path, scope: &str) -> Re
         content: body,
         description,
         allowed_tools,
+        disallowed_tools,
+        model,
         has_bash_commands,
         has_file_references,
         accepts_arguments,
+        created_at: Some(created_at),
+        modified_at: Some(modified_at),
+        tags,
     })
 }
 
@@ -196,6 +648,91 @@ fn find_markdown_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
     Ok(())
 }
 
+/// Temp files older than this are considered orphaned by an interrupted write
+const STALE_TEMP_FILE_AGE: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+
+/// Write a command file via a temp file and rename so readers never see a partial file
+fn write_command_atomic(file_path: &Path, content: &str) -> Result<()> {
+    let file_name = file_path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .context("Invalid command file path")?;
+    let temp_path = file_path.with_file_name(format!("{}.tmp.{}", file_name, std::process::id()));
+
+    fs::write(&temp_path, content).context("Failed to write temp file")?;
+    if let Err(e) = fs::rename(&temp_path, file_path) {
+        let _ = fs::remove_file(&temp_path);
+        return Err(e).context("Failed to move temp file into place");
+    }
+
+    Ok(())
+}
+
+/// Whether `name` ends in `.tmp.<digits>`, with `prefix` matching what comes before
+fn has_pid_temp_suffix(name: &str, prefix: impl Fn(&str) -> bool) -> bool {
+    name.rsplit_once(".tmp.").is_some_and(|(head, pid)| {
+        !pid.is_empty() && pid.bytes().all(|b| b.is_ascii_digit()) && prefix(head)
+    })
+}
+
+/// Whether `name` is a `<file>.tmp.<pid>` temp file left by `write_command_atomic`
+fn is_atomic_write_temp(name: &str) -> bool {
+    has_pid_temp_suffix(name, |head| !head.is_empty() && head != WRITE_PROBE_NAME)
+}
+
+/// Whether `name` is a `.write-probe.tmp.<pid>` file left by `probe_dir_writable`
+fn is_write_probe_temp(name: &str) -> bool {
+    has_pid_temp_suffix(name, |head| head == WRITE_PROBE_NAME)
+}
+
+/// Recursively remove stale temp files from interrupted writes, returning the count removed
+///
+/// Only the exact names `write_command_atomic` and `probe_dir_writable` produce are
+/// matched, so commands like `notes.tmp.md` are never touched.
+fn remove_stale_temp_files(dir: &Path) -> usize {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return 0,
+    };
+
+    let mut removed = 0;
+    for entry in entries.filter_map(|e| e.ok()) {
+        let path = entry.path();
+
+        if path.is_dir() {
+            removed += remove_stale_temp_files(&path);
+            continue;
+        }
+
+        let is_temp = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|n| is_atomic_write_temp(n) || is_write_probe_temp(n));
+        if !is_temp {
+            continue;
+        }
+
+        let is_stale = entry
+            .metadata()
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .map(|age| age >= STALE_TEMP_FILE_AGE)
+            .unwrap_or(false);
+        if is_stale {
+            match fs::remove_file(&path) {
+                Ok(_) => {
+                    debug!("Removed orphaned temp file: {:?}", path);
+                    removed += 1;
+                }
+                Err(e) => error!("Failed to remove temp file {:?}: {}", path, e),
+            }
+        }
+    }
+
+    removed
+}
+
 /// Create default/built-in slash commands
 fn create_default_commands() -> Vec<SlashCommand> {
     vec![
@@ -209,9 +746,14 @@ fn create_default_commands() -> Vec<SlashCommand> {
             content: "Add additional working directories".to_string(),
             description: Some("Add additional working directories".to_string()),
             allowed_tools: vec![],
+            disallowed_tools: vec![],
+            model: None,
             has_bash_commands: false,
             has_file_references: false,
             accepts_arguments: false,
+            created_at: None,
+            modified_at: None,
+            tags: vec![],
         },
         SlashCommand {
             id: "default-init".to_string(),
@@ -223,9 +765,14 @@ fn create_default_commands() -> Vec<SlashCommand> {
             content: "Initialize project with CLAUDE.md guide".to_string(),
             description: Some("Initialize project with CLAUDE.md guide".to_string()),
             allowed_tools: vec![],
+            disallowed_tools: vec![],
+            model: None,
             has_bash_commands: false,
             has_file_references: false,
             accepts_arguments: false,
+            created_at: None,
+            modified_at: None,
+            tags: vec![],
         },
         SlashCommand {
             id: "default-review".to_string(),
@@ -237,18 +784,29 @@ fn create_default_commands() -> Vec<SlashCommand> {
             content: "Request code review".to_string(),
             description: Some("Request code review".to_string()),
             allowed_tools: vec![],
+            disallowed_tools: vec![],
+            model: None,
             has_bash_commands: false,
             has_file_references: false,
             accepts_arguments: false,
+            created_at: None,
+            modified_at: None,
+            tags: vec![],
         },
     ]
 }
 
 /// Discover all custom slash commands
+///
+/// With `include_content` set to false, command bodies are left empty to keep
+/// large listings small; `slash_command_get` returns the full command.
 #[tauri::command]
 pub async fn slash_commands_list(
     project_path: Option<String>,
-) -> Result<Vec<SlashCommand>, String> {
+    find_project_root: Option<bool>,
+    tags: Option<Vec<String>>,
+    include_content: Option<bool>,
+) -> Result<Vec<SlashCommand>, CommandError> {
     info!("Discovering slash commands");
     let mut commands = Vec::new();
 
@@ -257,7 +815,10 @@ pub async fn slash_commands_list(
 
     // Load project commands if project path is provided
     if let Some(proj_path) = project_path {
-        let project_commands_dir = PathBuf::from(&proj_path).join(".claude").join("commands");
+        // Optionally resolve a subdirectory to its repository root
+        let project_dir =
+            super::claude::resolve_project_dir(&proj_path, find_project_root.unwrap_or(false));
+        let project_commands_dir = project_dir.join(".claude").join("commands");
         if project_commands_dir.exists() {
             debug!("Scanning project commands at: {:?}", project_commands_dir);
 
@@ -281,8 +842,7 @@ pub async fn slash_commands_list(
     }
 
     // Load user commands
-    if let Some(home_dir) = dirs::home_dir() {
-        let user_commands_dir = home_dir.join(".claude").join("commands");
+    if let Ok(user_commands_dir) = user_commands_dir() {
         if user_commands_dir.exists() {
             debug!("Scanning user commands at: {:?}", user_commands_dir);
 
@@ -305,51 +865,141 @@ pub async fn slash_commands_list(
         }
     }
 
+    if let Some(tags) = tags.filter(|t| !t.is_empty()) {
+        commands.retain(|cmd| has_all_tags(cmd, &tags));
+    }
+
+    if !include_content.unwrap_or(true) {
+        for cmd in &mut commands {
+            cmd.content.clear();
+        }
+    }
+
     info!("Found {} slash commands", commands.len());
     Ok(commands)
 }
 
+/// Whether a command carries every one of `tags` (case-insensitive)
+fn has_all_tags(command: &SlashCommand, tags: &[String]) -> bool {
+    tags.iter().all(|wanted| {
+        command
+            .tags
+            .iter()
+            .any(|tag| tag.eq_ignore_ascii_case(wanted.trim()))
+    })
+}
+
+/// A tag and how many commands use it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandTagCount {
+    pub tag: String,
+    pub count: usize,
+}
+
+/// List the distinct tags across all commands with their usage counts
+///
+/// Tags are grouped case-insensitively and sorted by count, then name.
+#[tauri::command]
+pub async fn list_command_tags(
+    project_path: Option<String>,
+) -> Result<Vec<CommandTagCount>, CommandError> {
+    let commands = slash_commands_list(project_path, None, None, None).await?;
+
+    let mut counts: std::collections::HashMap<String, CommandTagCount> =
+        std::collections::HashMap::new();
+    for command in &commands {
+        let mut seen = std::collections::HashSet::new();
+        for tag in &command.tags {
+            let tag = tag.trim();
+            if tag.is_empty() || !seen.insert(tag.to_lowercase()) {
+                continue;
+            }
+            counts
+                .entry(tag.to_lowercase())
+                .or_insert_with(|| CommandTagCount {
+                    tag: tag.to_string(),
+                    count: 0,
+                })
+                .count += 1;
+        }
+    }
+
+    let mut tags: Vec<CommandTagCount> = counts.into_values().collect();
+    tags.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.tag.cmp(&b.tag)));
+    Ok(tags)
+}
+
 /// Get a single slash command by ID
 #[tauri::command]
-pub async fn slash_command_get(command_id: String) -> Result<SlashCommand, String> {
+pub async fn slash_command_get(command_id: String) -> Result<SlashCommand, CommandError> {
     debug!("Getting slash command: {}", command_id);
 
     // Parse the ID to determine scope and reconstruct file path
     let parts: Vec<&str> = command_id.split('-').collect();
     if parts.len() < 2 {
-        return Err("Invalid command ID".to_string());
+        return Err(CommandError::InvalidInput("Invalid command ID".to_string()));
     }
 
     // The actual implementation would need to reconstruct the path and reload the command
     // For now, we'll list all commands and find the matching one
-    let commands = slash_commands_list(None).await?;
+    let commands = slash_commands_list(None, None, None, None).await?;
 
     commands
         .into_iter()
         .find(|cmd| cmd.id == command_id)
-        .ok_or_else(|| format!("Command not found: {}", command_id))
+        .ok_or_else(|| CommandError::NotFound(format!("Command not found: {}", command_id)))
 }
 
 /// Create or update a slash command
+///
+/// With the `command_metadata_sidecar` setting enabled, metadata is written to a
+/// `<name>.md.meta.json` sidecar and the `.md` file holds only the body. With
+/// `overwrite` set to false, an existing command is an `AlreadyExists` error.
 #[tauri::command]
 pub async fn slash_command_save(
+    app: AppHandle,
     scope: String,
     name: String,
     namespace: Option<String>,
     content: String,
     description: Option<String>,
     allowed_tools: Vec<String>,
+    disallowed_tools: Option<Vec<String>>,
+    model: Option<String>,
     project_path: Option<String>,
-) -> Result<SlashCommand, String> {
+    auto_normalize: Option<bool>,
+    write_timestamps: Option<bool>,
+    tags: Option<Vec<String>>,
+    overwrite: Option<bool>,
+) -> Result<SlashCommand, CommandError> {
+    let project_path = super::claude::or_current_project(project_path);
     info!("Saving slash command: {} in scope: {}", name, scope);
 
+    // Optionally turn pasted names like "Review PR" into "review-pr"
+    let name = if auto_normalize.unwrap_or(false) {
+        let normalized = normalize_command_name(&name);
+        if !normalized.is_empty() && !is_valid_command_segment(&normalized) {
+            return Err(CommandError::InvalidName(format!(
+                "Invalid command name: {}",
+                normalized
+            )));
+        }
+        normalized
+    } else {
+        name
+    };
+
     // Validate inputs
     if name.is_empty() {
-        return Err("Command name cannot be empty".to_string());
+        return Err(CommandError::InvalidName(
+            "Command name cannot be empty".to_string(),
+        ));
     }
 
     if !["project", "user"].contains(&scope.as_str()) {
-        return Err("Invalid scope. Must be 'project' or 'user'".to_string());
+        return Err(CommandError::InvalidInput(
+            "Invalid scope. Must be 'project' or 'user'".to_string(),
+        ));
     }
 
     // Determine base directory
@@ -357,13 +1007,12 @@ pub async fn slash_command_save(
         if let Some(proj_path) = project_path {
             PathBuf::from(proj_path).join(".claude").join("commands")
         } else {
-            return Err("Project path required for project scope".to_string());
+            return Err(CommandError::InvalidInput(
+                "Project path required for project scope".to_string(),
+            ));
         }
     } else {
-        dirs::home_dir()
-            .ok_or_else(|| "Could not find home directory".to_string())?
-            .join(".claude")
-            .join("commands")
+        user_commands_dir()?
     };
 
     // Build file path
@@ -375,97 +1024,2049 @@ pub async fn slash_command_save(
     }
 
     // Create directories if needed
-    fs::create_dir_all(&file_path).map_err(|e| format!("Failed to create directories: {}", e))?;
+    fs::create_dir_all(&file_path)
+        .map_err(|e| CommandError::Io(format!("Failed to create directories: {}", e)))?;
 
     // Add filename
     file_path = file_path.join(format!("{}.md", name));
+    if !overwrite.unwrap_or(true) && file_path.exists() {
+        return Err(CommandError::AlreadyExists(format!(
+            "Command already exists: {}",
+            name
+        )));
+    }
+
+    // Optionally record timestamps in frontmatter so they survive copying,
+    // keeping the original creation time when updating an existing command
+    let timestamps = if write_timestamps.unwrap_or(false) {
+        let now = chrono::Utc::now().to_rfc3339();
+        let created = fs::read_to_string(&file_path)
+            .ok()
+            .and_then(|existing| parse_markdown_with_frontmatter(&existing).ok())
+            .and_then(|(fm, _)| merge_command_sidecar(fm, read_command_sidecar(&file_path)))
+            .and_then(|fm| fm.created_at)
+            .or_else(|| {
+                fs::metadata(&file_path)
+                    .ok()
+                    .and_then(|m| m.created().or_else(|_| m.modified()).ok())
+                    .map(format_system_time)
+            })
+            .unwrap_or_else(|| now.clone());
+        Some((created, now))
+    } else {
+        None
+    };
 
     // Build content with frontmatter
     let mut full_content = String::new();
 
-    // Add frontmatter if we have metadata
-    if description.is_some() || !allowed_tools.is_empty() {
+    let model = model.filter(|m| !m.trim().is_empty());
+    let disallowed_tools = disallowed_tools.unwrap_or_default();
+    // Without new tags, keep whatever the command already has
+    let tags: Option<Vec<String>> = match tags {
+        Some(tags) => Some(
+            tags.into_iter()
+                .map(|tag| tag.trim().to_string())
+                .filter(|tag| !tag.is_empty())
+                .collect(),
+        ),
+        None => existing_command_tags(&file_path),
+    };
+    let sidecar_path = command_sidecar_path(&file_path);
+    let sidecar_mode = use_metadata_sidecar(&app);
+
+    if sidecar_mode {
+        let (created_at, modified_at) = timestamps.unzip();
+        let sidecar = CommandMetadataSidecar {
+            description,
+            model,
+            allowed_tools: Some(allowed_tools).filter(|t| !t.is_empty()),
+            disallowed_tools: Some(disallowed_tools).filter(|t| !t.is_empty()),
+            tags: tags.filter(|t| !t.is_empty()),
+            created_at,
+            modified_at,
+        };
+        let sidecar_json = serde_json::to_string_pretty(&sidecar).map_err(|e| {
+            CommandError::Internal(format!("Failed to serialize command metadata: {}", e))
+        })?;
+        write_command_atomic(&sidecar_path, &sidecar_json)
+            .map_err(|e| CommandError::Io(format!("Failed to write command metadata: {}", e)))?;
+    } else if description.is_some()
+        || !allowed_tools.is_empty()
+        || !disallowed_tools.is_empty()
+        || model.is_some()
+        || tags.as_ref().is_some_and(|t| !t.is_empty())
+        || timestamps.is_some()
+    {
         full_content.push_str("---\n");
 
         if let Some(desc) = &description {
-            full_content.push_str(&format!("description: {}\n", desc));
+            full_content.push_str(&format!("description: {}\n", yaml_quote(desc)));
         }
 
         if !allowed_tools.is_empty() {
             full_content.push_str("allowed-tools:\n");
             for tool in &allowed_tools {
-                full_content.push_str(&format!("  - {}\n", tool));
+                full_content.push_str(&format!("  - {}\n", yaml_quote(tool)));
+            }
+        }
+
+        if !disallowed_tools.is_empty() {
+            full_content.push_str("disallowed-tools:\n");
+            for tool in &disallowed_tools {
+                full_content.push_str(&format!("  - {}\n", yaml_quote(tool)));
             }
         }
 
+        if let Some(m) = &model {
+            full_content.push_str(&format!("model: {}\n", yaml_quote(m)));
+        }
+
+        if let Some(tags) = tags.as_ref().filter(|t| !t.is_empty()) {
+            full_content.push_str("tags:\n");
+            for tag in tags {
+                full_content.push_str(&format!("  - {}\n", yaml_quote(tag)));
+            }
+        }
+
+        if let Some((created, modified)) = &timestamps {
+            full_content.push_str(&format!("created_at: \"{}\"\n", created));
+            full_content.push_str(&format!("modified_at: \"{}\"\n", modified));
+        }
+
         full_content.push_str("---\n\n");
     }
 
     full_content.push_str(&content);
 
     // Write file
-    fs::write(&file_path, &full_content)
-        .map_err(|e| format!("Failed to write command file: {}", e))?;
+    write_command_atomic(&file_path, &full_content)
+        .map_err(|e| CommandError::Io(format!("Failed to write command file: {}", e)))?;
+
+    // Metadata now lives in frontmatter, so a leftover sidecar would only go stale
+    if !sidecar_mode && sidecar_path.exists() {
+        let _ = fs::remove_file(&sidecar_path);
+    }
 
     // Load and return the saved command
     load_command_from_file(&file_path, &base_dir, &scope)
-        .map_err(|e| format!("Failed to load saved command: {}", e))
+        .map_err(|e| CommandError::Io(format!("Failed to load saved command: {}", e)))
 }
 
-/// Delete a slash command
-#[tauri::command]
-pub async fn slash_command_delete(
-    command_id: String,
-    project_path: Option<String>,
-) -> Result<String, String> {
-    info!("Deleting slash command: {}", command_id);
+/// Whether command metadata should be written to a sidecar instead of frontmatter
+fn use_metadata_sidecar(app: &AppHandle) -> bool {
+    app.try_state::<super::agents::AgentDb>()
+        .and_then(|db| {
+            let conn = db.0.lock().ok()?;
+            super::agents::read_setting(&conn, "command_metadata_sidecar")
+                .ok()
+                .flatten()
+        })
+        .is_some_and(|value| value == "true")
+}
 
-    // First, we need to determine if this is a project command by parsing the ID
-    let is_project_command = command_id.starts_with("project-");
+/// Convert a command name to kebab-case: lowercased, with whitespace runs turned into
+/// single hyphens and leading/trailing hyphens removed
+fn normalize_command_name(name: &str) -> String {
+    name.split_whitespace()
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join("-")
+        .split('-')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
 
-    // If it's a project command and we don't have a project path, error out
-    if is_project_command && project_path.is_none() {
-        return Err("Project path required to delete project commands".to_string());
+/// Check that a command name or namespace component is safe to use as a path segment
+fn is_valid_command_segment(segment: &str) -> bool {
+    !segment.is_empty()
+        && !segment.starts_with('.')
+        && segment
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_' || c == '.')
+}
+
+/// Import commands into `base_dir`, reporting conflicts with existing commands
+///
+/// With `dry_run` set, the result is populated as the import would turn out but
+/// nothing is written. A cancelled import stops before the next command and
+/// records the cancellation in `errors`.
+fn import_exported_commands(
+    base_dir: &Path,
+    commands: Vec<ExportedCommand>,
+    overwrite: bool,
+    dry_run: bool,
+    progress: &mut ImportProgress,
+) -> CommandImportResult {
+    let mut result = CommandImportResult::default();
+    let mut planned = std::collections::HashSet::new();
+
+    for command in commands {
+        if progress.is_cancelled() {
+            result.errors.push(format!(
+                "Import cancelled after {} of {} commands",
+                progress.processed, progress.total
+            ));
+            break;
+        }
+
+        let display_name = match &command.namespace {
+            Some(ns) => format!("{}:{}", ns, command.name),
+            None => command.name.clone(),
+        };
+        progress.advance(&display_name);
+
+        let namespace_parts: Vec<&str> = command
+            .namespace
+            .as_deref()
+            .map(|ns| ns.split(':').collect())
+            .unwrap_or_default();
+        if !is_valid_command_segment(&command.name)
+            || !namespace_parts.iter().all(|p| is_valid_command_segment(p))
+        {
+            result
+                .errors
+                .push(format!("{}: invalid command name", display_name));
+            continue;
+        }
+
+        let mut file_path = base_dir.to_path_buf();
+        for component in &namespace_parts {
+            file_path = file_path.join(component);
+        }
+        file_path = file_path.join(format!("{}.md", command.name));
+
+        let exists = file_path.exists() || planned.contains(&file_path);
+        if exists && !overwrite {
+            result.conflicts.push(display_name);
+            continue;
+        }
+
+        if dry_run {
+            planned.insert(file_path);
+            if exists {
+                result.overwritten.push(display_name);
+            } else {
+                result.imported.push(display_name);
+            }
+            continue;
+        }
+
+        let written = file_path
+            .parent()
+            .context("Invalid command path")
+            .and_then(|parent| fs::create_dir_all(parent).context("Failed to create directories"))
+            .and_then(|_| write_command_atomic(&file_path, &command.content));
+
+        match written {
+            Ok(_) if exists => result.overwritten.push(display_name),
+            Ok(_) => result.imported.push(display_name),
+            Err(e) => result.errors.push(format!("{}: {}", display_name, e)),
+        }
     }
 
-    // List all commands (including project commands if applicable)
-    let commands = slash_commands_list(project_path).await?;
+    result
+}
 
-    // Find the command by ID
-    let command = commands
-        .into_iter()
-        .find(|cmd| cmd.id == command_id)
-        .ok_or_else(|| format!("Command not found: {}", command_id))?;
+/// Read every `.md` command under `dir` as an export entry, collecting per-file errors
+fn read_exported_commands(dir: &Path) -> Result<(Vec<ExportedCommand>, Vec<String>)> {
+    let mut md_files = Vec::new();
+    find_markdown_files(dir, &mut md_files)?;
 
-    // Delete the file
-    fs::remove_file(&command.file_path)
-        .map_err(|e| format!("Failed to delete command file: {}", e))?;
+    let mut commands = Vec::new();
+    let mut errors = Vec::new();
+    for file_path in md_files {
+        let entry = extract_command_info(&file_path, dir).and_then(|(name, namespace)| {
+            let content = fs::read_to_string(&file_path).context("Failed to read command file")?;
+            Ok(ExportedCommand {
+                name,
+                namespace,
+                content,
+                scope: None,
+            })
+        });
+        match entry {
+            Ok(command) => commands.push(command),
+            Err(e) => errors.push(format!("{}: {}", file_path.display(), e)),
+        }
+    }
 
-    // Clean up empty directories
-    if let Some(parent) = Path::new(&command.file_path).parent() {
-        let _ = remove_empty_dirs(parent);
+    Ok((commands, errors))
+}
+
+/// Magic bytes at the start of every gzip member
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Serialize an export bundle as JSON, optionally gzip-compressed
+fn encode_command_bundle(commands: &[ExportedCommand], compress: bool) -> Result<Vec<u8>> {
+    use std::io::Write;
+
+    let json = serde_json::to_vec_pretty(commands).context("Failed to serialize commands")?;
+    if compress {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder
+            .write_all(&json)
+            .and_then(|_| encoder.finish())
+            .context("Failed to compress commands")
+    } else {
+        Ok(json)
     }
+}
 
-    Ok(format!("Deleted command: {}", command.full_command))
+/// Parse an export bundle, transparently decompressing gzip payloads
+fn decode_command_bundle(bytes: &[u8]) -> Result<Vec<ExportedCommand>> {
+    use std::io::Read;
+
+    let json = if bytes.starts_with(&GZIP_MAGIC) {
+        let mut json = Vec::new();
+        flate2::read::MultiGzDecoder::new(bytes)
+            .read_to_end(&mut json)
+            .context("Failed to decompress commands")?;
+        json
+    } else {
+        bytes.to_vec()
+    };
+    serde_json::from_slice(&json).context("Invalid command export file")
 }
 
-/// Remove empty directories recursively
-fn remove_empty_dirs(dir: &Path) -> Result<()> {
-    if !dir.exists() {
-        return Ok(());
+/// Check that an export target is an absolute file path in an existing directory
+///
+/// Exports from the web server and automation have no save dialog to pick a sane
+/// location, so the path is validated before anything is written.
+pub fn validate_export_path(file_path: &str) -> Result<PathBuf, String> {
+    let path = PathBuf::from(file_path.trim());
+    if !path.is_absolute() {
+        return Err(format!("Export path must be absolute: {}", file_path));
+    }
+    if path.is_dir() {
+        return Err(format!("Export path is a directory: {}", file_path));
     }
+    match path.parent() {
+        Some(parent) if parent.is_dir() => Ok(path),
+        _ => Err(format!(
+            "Export directory does not exist: {}",
+            path.parent().unwrap_or(&path).display()
+        )),
+    }
+}
 
-    // Check if directory is empty
-    let is_empty = fs::read_dir(dir)?.next().is_none();
+/// Export all user commands to a JSON file
+///
+/// When `project_path` is given, that project's commands are included too,
+/// tagged with the "project" scope so an import can restore them there.
+/// With `compress` set the JSON is gzip-compressed (conventionally `.json.gz`);
+/// plain JSON stays the default so existing tooling can read the file.
+#[tauri::command]
+pub async fn export_commands_to_file(
+    file_path: String,
+    compress: Option<bool>,
+    project_path: Option<String>,
+) -> Result<usize, CommandError> {
+    let compress = compress.unwrap_or(false);
+    info!(
+        "Exporting slash commands to: {} (compressed: {}, project: {:?})",
+        file_path, compress, project_path
+    );
+    let file_path = validate_export_path(&file_path).map_err(CommandError::InvalidInput)?;
 
-    if is_empty {
-        fs::remove_dir(dir)?;
+    let (bytes, count) = build_command_export(compress, project_path.as_deref())?;
+    fs::write(&file_path, bytes)
+        .map_err(|e| CommandError::Io(format!("Failed to write export file: {}", e)))?;
 
-        // Try to remove parent if it's also empty
-        if let Some(parent) = dir.parent() {
-            let _ = remove_empty_dirs(parent);
-        }
+    Ok(count)
+}
+
+/// Serialize user (and optionally project) commands into an export bundle
+///
+/// Returns the encoded bundle and the number of commands in it.
+pub fn build_command_export(
+    compress: bool,
+    project_path: Option<&str>,
+) -> Result<(Vec<u8>, usize), String> {
+    let user_commands_dir = user_commands_dir()?;
+
+    let (mut commands, mut errors) = read_exported_commands(&user_commands_dir)
+        .map_err(|e| format!("Failed to read commands: {}", e))?;
+
+    if let Some(proj_path) = project_path {
+        let project_commands_dir = PathBuf::from(proj_path).join(".claude").join("commands");
+        let (project_commands, project_errors) = read_exported_commands(&project_commands_dir)
+            .map_err(|e| format!("Failed to read project commands: {}", e))?;
+        commands.extend(project_commands.into_iter().map(|mut cmd| {
+            cmd.scope = Some("project".to_string());
+            cmd
+        }));
+        errors.extend(project_errors);
     }
 
-    Ok(())
+    for e in &errors {
+        error!("Skipping command during export: {}", e);
+    }
+
+    let bytes = encode_command_bundle(&commands, compress).map_err(|e| e.to_string())?;
+    Ok((bytes, commands.len()))
+}
+
+/// Import commands from a file written by `export_commands_to_file`
+///
+/// Compressed and plain JSON exports are both accepted. Project-scoped
+/// entries are restored into `project_path` and reported as errors when none
+/// is given. With an `import_id`, progress is emitted as
+/// `command-import-progress:{import_id}` and `cancel_command_import` can stop it.
+#[tauri::command]
+pub async fn import_commands_from_file(
+    app: AppHandle,
+    file_path: String,
+    overwrite: bool,
+    dry_run: Option<bool>,
+    project_path: Option<String>,
+    import_id: Option<String>,
+) -> Result<CommandImportResult, CommandError> {
+    let dry_run = dry_run.unwrap_or(false);
+    info!(
+        "Importing slash commands from file: {} (dry run: {})",
+        file_path, dry_run
+    );
+
+    let bytes = fs::read(&file_path)
+        .map_err(|e| CommandError::Io(format!("Failed to read import file: {}", e)))?;
+    let commands =
+        decode_command_bundle(&bytes).map_err(|e| CommandError::InvalidInput(e.to_string()))?;
+
+    let user_commands_dir = user_commands_dir()?;
+
+    let (project_commands, user_commands): (Vec<_>, Vec<_>) = commands
+        .into_iter()
+        .partition(|cmd| cmd.scope.as_deref() == Some("project"));
+
+    let mut progress = ImportProgress::new(
+        &app,
+        import_id.as_deref(),
+        user_commands.len() + project_commands.len(),
+    );
+    let mut result = import_exported_commands(
+        &user_commands_dir,
+        user_commands,
+        overwrite,
+        dry_run,
+        &mut progress,
+    );
+
+    match &project_path {
+        Some(_) if progress.is_cancelled() => {}
+        Some(proj_path) => {
+            let project_commands_dir = PathBuf::from(proj_path).join(".claude").join("commands");
+            let project_result = import_exported_commands(
+                &project_commands_dir,
+                project_commands,
+                overwrite,
+                dry_run,
+                &mut progress,
+            );
+            result.imported.extend(project_result.imported);
+            result.overwritten.extend(project_result.overwritten);
+            result.conflicts.extend(project_result.conflicts);
+            result.errors.extend(project_result.errors);
+        }
+        None => {
+            result.errors.extend(
+                project_commands
+                    .iter()
+                    .map(|cmd| format!("{}: project path required for project command", cmd.name)),
+            );
+        }
+    }
+    drop(progress);
+
+    Ok(result)
+}
+
+/// Bulk-import `.md` command files from a local directory into the user commands directory
+///
+/// Subdirectories become namespaces, mirroring how commands are laid out on disk.
+/// With `dry_run` set, reports what would be imported without touching the filesystem.
+/// Progress and cancellation work as in `import_commands_from_file`.
+#[tauri::command]
+pub async fn import_commands_from_directory(
+    app: AppHandle,
+    dir: String,
+    overwrite: bool,
+    dry_run: Option<bool>,
+    import_id: Option<String>,
+) -> Result<CommandImportResult, CommandError> {
+    let dry_run = dry_run.unwrap_or(false);
+    info!(
+        "Importing slash commands from directory: {} (dry run: {})",
+        dir, dry_run
+    );
+
+    let source_dir = PathBuf::from(&dir);
+    if !source_dir.is_dir() {
+        return Err(CommandError::InvalidInput(format!(
+            "Not a directory: {}",
+            dir
+        )));
+    }
+
+    let (commands, read_errors) = read_exported_commands(&source_dir)
+        .map_err(|e| CommandError::Io(format!("Failed to scan directory: {}", e)))?;
+
+    let user_commands_dir = user_commands_dir()?;
+
+    let mut progress = ImportProgress::new(&app, import_id.as_deref(), commands.len());
+    let mut result = import_exported_commands(
+        &user_commands_dir,
+        commands,
+        overwrite,
+        dry_run,
+        &mut progress,
+    );
+    drop(progress);
+    result.errors.extend(read_errors);
+
+    info!(
+        "{} {} commands ({} overwritten, {} conflicts, {} errors)",
+        if dry_run { "Would import" } else { "Imported" },
+        result.imported.len(),
+        result.overwritten.len(),
+        result.conflicts.len(),
+        result.errors.len()
+    );
+    Ok(result)
+}
+
+/// Ask a running command import to stop before its next command
+///
+/// Ignored when no import with `import_id` is running.
+#[tauri::command]
+pub async fn cancel_command_import(import_id: String) -> Result<(), CommandError> {
+    let mut running = RUNNING_IMPORTS
+        .lock()
+        .map_err(|e| format!("Failed to lock import state: {}", e))?;
+    match running.iter_mut().find(|(id, _)| *id == import_id) {
+        Some((_, cancelled)) => {
+            info!("Cancelling command import: {}", import_id);
+            *cancelled = true;
+        }
+        None => debug!(
+            "Ignoring cancel for import that isn't running: {}",
+            import_id
+        ),
+    }
+    Ok(())
+}
+
+/// Delete a slash command
+#[tauri::command]
+pub async fn slash_command_delete(
+    command_id: String,
+    project_path: Option<String>,
+) -> Result<String, CommandError> {
+    let project_path = super::claude::or_current_project(project_path);
+    info!("Deleting slash command: {}", command_id);
+
+    // First, we need to determine if this is a project command by parsing the ID
+    let is_project_command = command_id.starts_with("project-");
+
+    // If it's a project command and we don't have a project path, error out
+    if is_project_command && project_path.is_none() {
+        return Err(CommandError::InvalidInput(
+            "Project path required to delete project commands".to_string(),
+        ));
+    }
+
+    // List all commands (including project commands if applicable)
+    let commands = slash_commands_list(project_path, None, None, None).await?;
+
+    // Find the command by ID
+    let command = commands
+        .into_iter()
+        .find(|cmd| cmd.id == command_id)
+        .ok_or_else(|| CommandError::NotFound(format!("Command not found: {}", command_id)))?;
+
+    delete_command_files(&command)?;
+
+    Ok(format!("Deleted command: {}", command.full_command))
+}
+
+/// Remove a command's file and sidecar, then any directories left empty
+fn delete_command_files(command: &SlashCommand) -> Result<(), CommandError> {
+    fs::remove_file(&command.file_path)
+        .map_err(|e| CommandError::Io(format!("Failed to delete command file: {}", e)))?;
+    let _ = fs::remove_file(command_sidecar_path(Path::new(&command.file_path)));
+    record_command_tombstone(command);
+
+    if let Some(parent) = Path::new(&command.file_path).parent() {
+        let _ = remove_empty_dirs(parent);
+    }
+    Ok(())
+}
+
+/// Directory under the app data dir holding one tombstone log per commands directory
+const TOMBSTONE_DIR: &str = "command-tombstones";
+
+/// Where tombstones used to be kept, inside the commands directory itself
+const LEGACY_TOMBSTONE_FILE: &str = ".deleted-commands.json";
+
+/// Tombstones kept per commands directory; older entries are dropped first
+const MAX_TOMBSTONES: usize = 500;
+
+/// How long tombstones are kept before they are pruned
+const TOMBSTONE_RETENTION: chrono::Duration = chrono::Duration::days(30);
+
+/// A command deleted through opcode, kept so sync clients can mirror the deletion
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandTombstone {
+    pub id: String,
+    pub full_command: String,
+    pub scope: String,
+    pub deleted_at: String,
+}
+
+/// Bounded deletion history for one commands directory
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct TombstoneLog {
+    /// Latest `deleted_at` among pruned entries; deletions up to here may be missing
+    truncated_before: Option<String>,
+    entries: Vec<CommandTombstone>,
+}
+
+impl TombstoneLog {
+    fn load(path: &Path) -> Option<Self> {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+    }
+
+    fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(self)?;
+        write_command_atomic(path, &content)
+    }
+
+    /// The log for `base_dir`, picking up one left in the commands directory by
+    /// older versions
+    fn load_for(base_dir: &Path) -> Self {
+        tombstone_log_path(base_dir)
+            .ok()
+            .and_then(|path| Self::load(&path))
+            .or_else(|| Self::load(&base_dir.join(LEGACY_TOMBSTONE_FILE)))
+            .unwrap_or_default()
+    }
+
+    fn save_for(&self, base_dir: &Path) -> Result<()> {
+        let path = tombstone_log_path(base_dir).map_err(anyhow::Error::msg)?;
+        self.save(&path)?;
+        let _ = fs::remove_file(base_dir.join(LEGACY_TOMBSTONE_FILE));
+        Ok(())
+    }
+
+    /// Drop entries past the retention window or over the size cap
+    fn prune(&mut self, now: chrono::DateTime<chrono::Utc>) {
+        let cutoff = (now - TOMBSTONE_RETENTION).to_rfc3339();
+        let overflow = self.entries.len().saturating_sub(MAX_TOMBSTONES);
+        let mut index = 0;
+        let mut pruned_until = self.truncated_before.take();
+        self.entries.retain(|entry| {
+            index += 1;
+            let keep = index > overflow && entry.deleted_at >= cutoff;
+            if !keep
+                && pruned_until
+                    .as_deref()
+                    .is_none_or(|p| entry.deleted_at.as_str() > p)
+            {
+                pruned_until = Some(entry.deleted_at.clone());
+            }
+            keep
+        });
+        self.truncated_before = pruned_until;
+    }
+}
+
+/// Tombstone log for a commands directory, kept in the app data dir so it neither
+/// shows up in the commands directory nor gets synced or committed with it
+fn tombstone_log_path(base_dir: &Path) -> Result<PathBuf, String> {
+    use sha2::{Digest, Sha256};
+
+    let mut hasher = Sha256::new();
+    hasher.update(base_dir.to_string_lossy().as_bytes());
+    let key = format!("{:x}", hasher.finalize());
+    Ok(super::storage::current_app_data_dir()?
+        .join(TOMBSTONE_DIR)
+        .join(format!("{}.json", &key[..16])))
+}
+
+/// The commands directory a command file lives in, for locating its tombstone log
+fn command_base_dir(command: &SlashCommand) -> Option<PathBuf> {
+    if command.scope == "user" {
+        return user_commands_dir().ok();
+    }
+    Path::new(&command.file_path)
+        .ancestors()
+        .find(|dir| {
+            dir.file_name().is_some_and(|name| name == "commands")
+                && dir
+                    .parent()
+                    .and_then(|p| p.file_name())
+                    .is_some_and(|name| name == ".claude")
+        })
+        .map(Path::to_path_buf)
+}
+
+/// Append a tombstone for a deleted command; failures are logged, not returned
+fn record_command_tombstone(command: &SlashCommand) {
+    let Some(base_dir) = command_base_dir(command) else {
+        return;
+    };
+
+    let now = chrono::Utc::now();
+    let mut log = TombstoneLog::load_for(&base_dir);
+    log.entries.push(CommandTombstone {
+        id: command.id.clone(),
+        full_command: command.full_command.clone(),
+        scope: command.scope.clone(),
+        deleted_at: now.to_rfc3339(),
+    });
+    log.prune(now);
+    if let Err(e) = log.save_for(&base_dir) {
+        error!(
+            "Failed to record deletion of {}: {}",
+            command.full_command, e
+        );
+    }
+}
+
+/// Commands changed and deleted since a point in time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandDelta {
+    /// Commands created or modified after `since`
+    pub changed: Vec<SlashCommand>,
+    /// Commands deleted through opcode after `since`
+    pub deleted: Vec<CommandTombstone>,
+    /// The tombstone history no longer covers `since`; do a full listing instead
+    pub resync_required: bool,
+    /// Pass this as `since` on the next call
+    pub server_time: String,
+}
+
+/// Whether a command changed after `since`, by its recorded or on-disk modification time
+fn modified_after(command: &SlashCommand, since: chrono::DateTime<chrono::Utc>) -> bool {
+    let recorded = command
+        .modified_at
+        .as_deref()
+        .and_then(|ts| chrono::DateTime::parse_from_rfc3339(ts).ok())
+        .map(|ts| ts.with_timezone(&chrono::Utc));
+    let on_disk = fs::metadata(&command.file_path)
+        .and_then(|m| m.modified())
+        .ok()
+        .map(chrono::DateTime::<chrono::Utc>::from);
+
+    match (recorded, on_disk) {
+        (None, None) => true,
+        (recorded, on_disk) => recorded.max(on_disk).is_some_and(|ts| ts > since),
+    }
+}
+
+/// List commands changed since `since` (RFC 3339) plus commands deleted since then
+///
+/// Deletions are only known for commands removed through opcode. Tombstones are
+/// kept for 30 days and at most 500 per commands directory; when `since` predates
+/// that history `resync_required` is set and the client should list everything.
+#[tauri::command]
+pub async fn list_commands_since(
+    since: String,
+    project_path: Option<String>,
+) -> Result<CommandDelta, CommandError> {
+    let server_time = chrono::Utc::now();
+    let since = chrono::DateTime::parse_from_rfc3339(&since)
+        .map_err(|e| CommandError::InvalidInput(format!("Invalid since timestamp: {}", e)))?
+        .with_timezone(&chrono::Utc);
+
+    let changed: Vec<SlashCommand> = slash_commands_list(project_path.clone(), None, None, None)
+        .await?
+        .into_iter()
+        .filter(|cmd| cmd.scope != "default" && modified_after(cmd, since))
+        .collect();
+
+    let mut base_dirs = vec![user_commands_dir()?];
+    if let Some(proj_path) = &project_path {
+        base_dirs.push(PathBuf::from(proj_path).join(".claude").join("commands"));
+    }
+
+    let since_str = since.to_rfc3339();
+    let mut deleted = Vec::new();
+    let mut resync_required = server_time - since > TOMBSTONE_RETENTION;
+    for base_dir in base_dirs {
+        let log = TombstoneLog::load_for(&base_dir);
+        if log
+            .truncated_before
+            .as_deref()
+            .is_some_and(|truncated| truncated >= since_str.as_str())
+        {
+            resync_required = true;
+        }
+        deleted.extend(log.entries.into_iter().filter(|entry| {
+            chrono::DateTime::parse_from_rfc3339(&entry.deleted_at)
+                .map(|ts| ts.with_timezone(&chrono::Utc) > since)
+                .unwrap_or(true)
+        }));
+    }
+    deleted.sort_by(|a, b| a.deleted_at.cmp(&b.deleted_at));
+
+    Ok(CommandDelta {
+        changed,
+        deleted,
+        resync_required,
+        server_time: server_time.to_rfc3339(),
+    })
+}
+
+/// Execution history file in the app data dir, one JSON entry per line
+const EXECUTION_LOG_FILE: &str = "command-history.jsonl";
+
+/// Where the execution history used to be kept, in the user commands directory
+const LEGACY_EXECUTION_LOG_FILE: &str = ".command-history.jsonl";
+
+/// Entries kept in the execution history; older ones are dropped on compaction
+const MAX_EXECUTION_LOG_ENTRIES: usize = 1000;
+
+/// Line count of the execution history file last appended to
+///
+/// Also serializes appends and compaction, so the count stays accurate.
+static EXECUTION_LOG_LINES: std::sync::Mutex<Option<(PathBuf, usize)>> =
+    std::sync::Mutex::new(None);
+
+/// One run of a slash command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandExecution {
+    /// Full command as invoked, e.g. "/project:optimize"
+    pub name: String,
+    pub project_path: String,
+    pub model: String,
+    /// Arguments passed after the command name
+    pub args: Option<String>,
+    pub timestamp: String,
+}
+
+/// Path of the execution history, moving one left in the user commands
+/// directory by older versions into place
+fn execution_log_path() -> Result<PathBuf, String> {
+    let log_path = super::storage::current_app_data_dir()?.join(EXECUTION_LOG_FILE);
+    if !log_path.exists() {
+        let legacy = user_commands_dir()?.join(LEGACY_EXECUTION_LOG_FILE);
+        if legacy.exists() {
+            if let Some(parent) = log_path.parent() {
+                let _ = fs::create_dir_all(parent);
+            }
+            if let Err(e) = fs::rename(&legacy, &log_path) {
+                error!("Failed to move command history to {:?}: {}", log_path, e);
+            }
+        }
+    }
+    Ok(log_path)
+}
+
+/// Number of lines in the file at `path`, or 0 if it can't be read
+fn count_lines(path: &Path) -> usize {
+    use std::io::BufRead;
+
+    fs::File::open(path)
+        .map(|file| std::io::BufReader::new(file).lines().count())
+        .unwrap_or(0)
+}
+
+/// Append an entry to the execution history at `log_path`
+///
+/// Once the file holds twice `MAX_EXECUTION_LOG_ENTRIES` lines it is rewritten
+/// with only the newest entries. The line count is kept in memory, so the file
+/// is only read in full when it is first appended to and when it is compacted.
+fn append_execution(log_path: &Path, execution: &CommandExecution) -> Result<()> {
+    use std::io::Write;
+
+    let mut state = EXECUTION_LOG_LINES
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    let mut lines = match state.as_ref() {
+        Some((path, lines)) if path == log_path => *lines,
+        _ => count_lines(log_path),
+    };
+
+    let line = serde_json::to_string(execution)?;
+    if let Some(parent) = log_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(log_path)
+        .context("Failed to open command history")?;
+    writeln!(file, "{}", line)?;
+    drop(file);
+    lines += 1;
+
+    if lines > MAX_EXECUTION_LOG_ENTRIES * 2 {
+        let content = fs::read_to_string(log_path)?;
+        let all: Vec<&str> = content.lines().collect();
+        let mut kept = all[all.len().saturating_sub(MAX_EXECUTION_LOG_ENTRIES)..].join("\n");
+        kept.push('\n');
+        write_command_atomic(log_path, &kept)?;
+        lines = all.len().min(MAX_EXECUTION_LOG_ENTRIES);
+    }
+    *state = Some((log_path.to_path_buf(), lines));
+    Ok(())
+}
+
+/// Read up to `limit` entries from the execution history, newest first
+fn read_executions(log_path: &Path, limit: usize) -> Vec<CommandExecution> {
+    let Ok(content) = fs::read_to_string(log_path) else {
+        return Vec::new();
+    };
+    content
+        .lines()
+        .rev()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .take(limit)
+        .collect()
+}
+
+/// Record a run of `command` in the execution history
+///
+/// This writes to disk; async callers should run it with `spawn_blocking`.
+pub fn record_command_execution(
+    command: &SlashCommand,
+    prompt: &str,
+    project_path: &str,
+    model: &str,
+) {
+    let args = prompt
+        .trim_start()
+        .split_once(char::is_whitespace)
+        .map(|(_, args)| args.trim().to_string())
+        .filter(|args| !args.is_empty());
+    let execution = CommandExecution {
+        name: command.full_command.clone(),
+        project_path: project_path.to_string(),
+        model: model.to_string(),
+        args,
+        timestamp: chrono::Utc::now().to_rfc3339(),
+    };
+
+    let result = execution_log_path()
+        .map_err(anyhow::Error::msg)
+        .and_then(|log_path| append_execution(&log_path, &execution));
+    if let Err(e) = result {
+        error!(
+            "Failed to record execution of {}: {}",
+            command.full_command, e
+        );
+    }
+}
+
+/// Record `prompt` in the execution history if it invokes a known slash command
+pub async fn record_prompt_execution(prompt: &str, project_path: &str, model: &str) {
+    if !prompt.trim_start().starts_with('/') {
+        return;
+    }
+    if let Some(command) = find_invoked_command(prompt, Some(project_path.to_string())).await {
+        let (prompt, project_path, model) = (
+            prompt.to_string(),
+            project_path.to_string(),
+            model.to_string(),
+        );
+        let _ = tokio::task::spawn_blocking(move || {
+            record_command_execution(&command, &prompt, &project_path, &model)
+        })
+        .await;
+    }
+}
+
+/// List the most recently executed slash commands, newest first
+///
+/// The history keeps roughly the last 1000 executions; `limit` defaults to 50.
+#[tauri::command]
+pub async fn list_command_executions(
+    limit: Option<usize>,
+) -> Result<Vec<CommandExecution>, CommandError> {
+    let log_path = execution_log_path()?;
+    let limit = limit.unwrap_or(50).min(MAX_EXECUTION_LOG_ENTRIES);
+    tokio::task::spawn_blocking(move || read_executions(&log_path, limit))
+        .await
+        .map_err(|e| CommandError::Io(format!("Failed to read command history: {}", e)))
+}
+
+/// Hash of a command body with line endings and surrounding whitespace normalized
+fn normalized_content_hash(content: &str) -> String {
+    use sha2::{Digest, Sha256};
+
+    let normalized = content
+        .lines()
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n");
+    let mut hasher = Sha256::new();
+    hasher.update(normalized.trim().as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// Commands whose bodies are identical after normalization
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateCommandGroup {
+    pub content_hash: String,
+    pub commands: Vec<SlashCommand>,
+}
+
+/// Group custom commands whose bodies match after whitespace normalization
+///
+/// Only reports candidates; nothing is changed until `merge_commands` is called.
+#[tauri::command]
+pub async fn find_duplicate_commands(
+    project_path: Option<String>,
+) -> Result<Vec<DuplicateCommandGroup>, CommandError> {
+    let commands = slash_commands_list(project_path, None, None, None).await?;
+
+    let mut groups: std::collections::HashMap<String, Vec<SlashCommand>> =
+        std::collections::HashMap::new();
+    for command in commands.into_iter().filter(|cmd| cmd.scope != "default") {
+        groups
+            .entry(normalized_content_hash(&command.content))
+            .or_default()
+            .push(command);
+    }
+
+    let mut duplicates: Vec<DuplicateCommandGroup> = groups
+        .into_iter()
+        .filter(|(_, commands)| commands.len() > 1)
+        .map(|(content_hash, mut commands)| {
+            commands.sort_by(|a, b| a.full_command.cmp(&b.full_command));
+            DuplicateCommandGroup {
+                content_hash,
+                commands,
+            }
+        })
+        .collect();
+    duplicates.sort_by(|a, b| a.commands[0].full_command.cmp(&b.commands[0].full_command));
+    Ok(duplicates)
+}
+
+/// Delete duplicates of `keep`, identified by command ID
+///
+/// Every command in `remove` must have the same normalized content as `keep`;
+/// otherwise nothing is deleted. Returns the removed commands' names.
+#[tauri::command]
+pub async fn merge_commands(
+    keep: String,
+    remove: Vec<String>,
+    project_path: Option<String>,
+) -> Result<Vec<String>, CommandError> {
+    let project_path = super::claude::or_current_project(project_path);
+    info!(
+        "Merging {} duplicate(s) into command {}",
+        remove.len(),
+        keep
+    );
+
+    let commands = slash_commands_list(project_path, None, None, None).await?;
+    let find = |id: &str| {
+        commands
+            .iter()
+            .find(|cmd| cmd.id == id && cmd.scope != "default")
+            .ok_or_else(|| CommandError::NotFound(format!("Command not found: {}", id)))
+    };
+
+    let kept = find(&keep)?;
+    let kept_hash = normalized_content_hash(&kept.content);
+
+    let mut to_remove = Vec::new();
+    for id in &remove {
+        if *id == keep {
+            return Err(CommandError::InvalidInput(
+                "Cannot remove the command being kept".to_string(),
+            ));
+        }
+        if to_remove.iter().any(|cmd: &&SlashCommand| cmd.id == *id) {
+            continue;
+        }
+        let command = find(id)?;
+        if normalized_content_hash(&command.content) != kept_hash {
+            return Err(CommandError::ContentMismatch(format!(
+                "{} differs from {}; not merging",
+                command.full_command, kept.full_command
+            )));
+        }
+        to_remove.push(command);
+    }
+
+    let mut removed = Vec::new();
+    for command in to_remove {
+        delete_command_files(command)?;
+        removed.push(command.full_command.clone());
+    }
+    Ok(removed)
+}
+
+/// Number of ranked results sent per event by the streaming search
+const SEARCH_BATCH_SIZE: usize = 50;
+
+/// A slash command search hit with its relevance score (higher is better)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlashCommandMatch {
+    pub command: SlashCommand,
+    pub score: u32,
+}
+
+/// Score how well a command matches a lowercase query, or None if it doesn't match
+fn score_command(command: &SlashCommand, query: &str) -> Option<u32> {
+    let name = command.name.to_lowercase();
+    let full_command = command.full_command.to_lowercase();
+
+    if name == query || full_command.trim_start_matches('/') == query {
+        Some(100)
+    } else if name.starts_with(query) {
+        Some(75)
+    } else if full_command.contains(query) {
+        Some(50)
+    } else if command
+        .description
+        .as_deref()
+        .map(|d| d.to_lowercase().contains(query))
+        .unwrap_or(false)
+    {
+        Some(25)
+    } else if command.content.to_lowercase().contains(query) {
+        Some(10)
+    } else {
+        None
+    }
+}
+
+/// Largest usage boost added to a match score
+///
+/// Kept below the smallest gap between match tiers (25 vs 10), so usage only
+/// reorders commands that match a query equally well.
+const MAX_USAGE_BOOST: u32 = 14;
+
+/// Score boost per command from the execution history, keyed by full command
+///
+/// Frequent runs count up to 10 points; a run in the last day adds 4 more, in the
+/// last week 2.
+fn usage_boosts(
+    executions: &[CommandExecution],
+    now: chrono::DateTime<chrono::Utc>,
+) -> std::collections::HashMap<String, u32> {
+    let mut counts: std::collections::HashMap<&str, (u32, Option<chrono::Duration>)> =
+        std::collections::HashMap::new();
+    for execution in executions {
+        let age = chrono::DateTime::parse_from_rfc3339(&execution.timestamp)
+            .ok()
+            .map(|ts| now - ts.with_timezone(&chrono::Utc));
+        let entry = counts.entry(execution.name.as_str()).or_insert((0, None));
+        entry.0 += 1;
+        if let Some(age) = age {
+            entry.1 = Some(entry.1.map_or(age, |newest| newest.min(age)));
+        }
+    }
+
+    counts
+        .into_iter()
+        .map(|(name, (count, newest))| {
+            let recency = match newest {
+                Some(age) if age < chrono::Duration::days(1) => 4,
+                Some(age) if age < chrono::Duration::days(7) => 2,
+                _ => 0,
+            };
+            let boost = (count.min(5) * 2 + recency).min(MAX_USAGE_BOOST);
+            (name.to_string(), boost)
+        })
+        .collect()
+}
+
+/// Score and rank commands against a query, best matches first
+///
+/// `boosts` adds a per-command usage bonus to the match score.
+fn rank_commands(
+    commands: Vec<SlashCommand>,
+    query: &str,
+    boosts: Option<&std::collections::HashMap<String, u32>>,
+) -> Vec<SlashCommandMatch> {
+    let mut matches: Vec<SlashCommandMatch> = commands
+        .into_iter()
+        .filter_map(|command| {
+            let boost = boosts
+                .and_then(|boosts| boosts.get(&command.full_command))
+                .copied()
+                .unwrap_or(0);
+            score_command(&command, query).map(|score| SlashCommandMatch {
+                command,
+                score: score + boost,
+            })
+        })
+        .collect();
+
+    matches.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then_with(|| a.command.full_command.cmp(&b.command.full_command))
+    });
+    matches
+}
+
+/// Search slash commands by name, description and content
+///
+/// With `tags`, only commands carrying all of them are searched. With
+/// `boost_by_usage`, frequently and recently executed commands rank first among
+/// equally good matches.
+#[tauri::command]
+pub async fn search_slash_commands(
+    query: String,
+    project_path: Option<String>,
+    tags: Option<Vec<String>>,
+    boost_by_usage: Option<bool>,
+) -> Result<Vec<SlashCommandMatch>, CommandError> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let commands = slash_commands_list(project_path, None, tags, None).await?;
+    let boosts = load_usage_boosts(boost_by_usage.unwrap_or(false)).await?;
+    Ok(rank_commands(commands, &query, boosts.as_ref()))
+}
+
+/// Usage boosts from the execution history, or None when boosting is off
+async fn load_usage_boosts(
+    enabled: bool,
+) -> Result<Option<std::collections::HashMap<String, u32>>, CommandError> {
+    if !enabled {
+        return Ok(None);
+    }
+    let log_path = execution_log_path()?;
+    let executions =
+        tokio::task::spawn_blocking(move || read_executions(&log_path, MAX_EXECUTION_LOG_ENTRIES))
+            .await
+            .map_err(|e| CommandError::Io(format!("Failed to read command history: {}", e)))?;
+    Ok(Some(usage_boosts(&executions, chrono::Utc::now())))
+}
+
+/// Search slash commands, emitting the ranked results in batches
+///
+/// Takes the same `tags` and `boost_by_usage` filters as `search_slash_commands` and
+/// ranks the whole list once, so batches arrive best matches first. Emits
+/// `slash-command-search:{search_id}` with each batch of `SlashCommandMatch` and
+/// `slash-command-search-complete:{search_id}` with the total match count.
+/// Intended for large command libraries; `search_slash_commands` is fine otherwise.
+#[tauri::command]
+pub async fn search_slash_commands_streaming(
+    app: AppHandle,
+    search_id: String,
+    query: String,
+    project_path: Option<String>,
+    tags: Option<Vec<String>>,
+    boost_by_usage: Option<bool>,
+) -> Result<(), CommandError> {
+    let query = query.trim().to_lowercase();
+    let matches = if query.is_empty() {
+        Vec::new()
+    } else {
+        let commands = slash_commands_list(project_path, None, tags, None).await?;
+        let boosts = load_usage_boosts(boost_by_usage.unwrap_or(false)).await?;
+        rank_commands(commands, &query, boosts.as_ref())
+    };
+
+    let total = matches.len();
+    for batch in matches.chunks(SEARCH_BATCH_SIZE) {
+        let _ = app.emit(&format!("slash-command-search:{}", search_id), batch);
+        // Let the UI process this batch before sending the next one
+        tokio::task::yield_now().await;
+    }
+
+    let _ = app.emit(
+        &format!("slash-command-search-complete:{}", search_id),
+        total,
+    );
+    Ok(())
+}
+
+/// Find the slash command a prompt invokes, if the prompt starts with one
+pub async fn find_invoked_command(
+    prompt: &str,
+    project_path: Option<String>,
+) -> Option<SlashCommand> {
+    let invoked = prompt
+        .split_whitespace()
+        .next()
+        .filter(|token| token.starts_with('/'))?;
+
+    slash_commands_list(project_path, None, None, None)
+        .await
+        .ok()?
+        .into_iter()
+        .find(|cmd| cmd.full_command == invoked)
+}
+
+/// Resolve the model to run a prompt with
+///
+/// Precedence: an explicit model from the caller, then the `model` declared in the
+/// frontmatter of the invoked slash command, then `default_model`.
+pub fn resolve_command_model(
+    command: Option<&SlashCommand>,
+    explicit_model: Option<String>,
+    default_model: &str,
+) -> String {
+    explicit_model
+        .filter(|m| !m.trim().is_empty())
+        .or_else(|| command.and_then(|cmd| cmd.model.clone()))
+        .unwrap_or_else(|| default_model.to_string())
+}
+
+/// Build the Claude CLI flags enforcing a command's `allowed-tools`/`disallowed-tools`
+pub fn command_tool_args(command: &SlashCommand) -> Vec<String> {
+    let mut args = Vec::new();
+    if !command.allowed_tools.is_empty() {
+        args.push("--allowedTools".to_string());
+        args.push(command.allowed_tools.join(","));
+    }
+    if !command.disallowed_tools.is_empty() {
+        args.push("--disallowedTools".to_string());
+        args.push(command.disallowed_tools.join(","));
+    }
+    args
+}
+
+/// Open a command's markdown file in the user's editor
+///
+/// `name` may be the bare command name, `namespace:name`, or the full command
+/// (e.g. "/project:frontend:component"). Project commands take precedence over
+/// user commands. Uses `$VISUAL`, then `$EDITOR`, then the OS default handler.
+#[tauri::command]
+pub async fn open_command_in_editor(
+    name: String,
+    project_path: Option<String>,
+) -> Result<String, CommandError> {
+    info!("Opening slash command in editor: {}", name);
+
+    let commands = slash_commands_list(project_path, None, None, None).await?;
+    let command = commands
+        .into_iter()
+        .filter(|cmd| !cmd.file_path.is_empty())
+        .find(|cmd| matches_command_name(cmd, &name))
+        .ok_or_else(|| CommandError::NotFound(format!("Command not found: {}", name)))?;
+
+    let file_path = command.file_path;
+    if !Path::new(&file_path).exists() {
+        return Err(CommandError::NotFound(format!(
+            "Command file does not exist: {}",
+            file_path
+        )));
+    }
+
+    let editor = std::env::var("VISUAL")
+        .ok()
+        .or_else(|| std::env::var("EDITOR").ok())
+        .filter(|e| !e.trim().is_empty());
+
+    let spawn_result = if let Some(editor) = editor {
+        // Editors may be configured with arguments, e.g. "code --wait"
+        let mut parts = editor.split_whitespace();
+        let program = parts.next().unwrap_or_default();
+        std::process::Command::new(program)
+            .args(parts)
+            .arg(&file_path)
+            .spawn()
+    } else if cfg!(target_os = "macos") {
+        std::process::Command::new("open").arg(&file_path).spawn()
+    } else if cfg!(target_os = "windows") {
+        std::process::Command::new("cmd")
+            .args(["/C", "start", ""])
+            .arg(&file_path)
+            .spawn()
+    } else {
+        std::process::Command::new("xdg-open")
+            .arg(&file_path)
+            .spawn()
+    };
+
+    spawn_result.map_err(|e| CommandError::Io(format!("Failed to launch editor: {}", e)))?;
+
+    Ok(file_path)
+}
+
+/// Result of probing whether the user commands directory can be written
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandsDirWritability {
+    pub path: String,
+    pub writable: bool,
+    /// OS error from the failed write probe, if any
+    pub error: Option<String>,
+    /// Whether permissions were changed to make the directory user-writable
+    pub repaired: bool,
+}
+
+/// File name stem of the temp file `probe_dir_writable` creates
+const WRITE_PROBE_NAME: &str = ".write-probe";
+
+/// Create (if needed) and remove a temp file in `dir`, the same way command saves write
+fn probe_dir_writable(dir: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(dir)?;
+    let probe = dir.join(format!("{}.tmp.{}", WRITE_PROBE_NAME, std::process::id()));
+    fs::write(&probe, b"")?;
+    fs::remove_file(&probe)
+}
+
+/// Add the owner read/write/execute bits to a directory's permissions
+#[cfg(unix)]
+fn make_dir_user_writable(dir: &Path) -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    let mut permissions = fs::metadata(dir)?.permissions();
+    permissions.set_mode(permissions.mode() | 0o700);
+    fs::set_permissions(dir, permissions)
+}
+
+/// Check that `dir` is writable, optionally trying to fix its permissions (Unix only)
+pub(crate) fn check_dir_writable(dir: &Path, repair: bool) -> CommandsDirWritability {
+    let mut result = CommandsDirWritability {
+        path: dir.to_string_lossy().to_string(),
+        writable: true,
+        error: None,
+        repaired: false,
+    };
+
+    let Err(e) = probe_dir_writable(dir) else {
+        return result;
+    };
+    result.writable = false;
+    result.error = Some(e.to_string());
+
+    if repair {
+        #[cfg(unix)]
+        {
+            match make_dir_user_writable(dir).and_then(|_| probe_dir_writable(dir)) {
+                Ok(()) => {
+                    info!("Repaired permissions on commands directory {:?}", dir);
+                    result.writable = true;
+                    result.error = None;
+                    result.repaired = true;
+                }
+                Err(repair_err) => {
+                    result.error = Some(format!("{} (repair failed: {})", e, repair_err));
+                }
+            }
+        }
+        #[cfg(not(unix))]
+        {
+            result.error = Some(format!(
+                "{} (automatic repair is only supported on Unix)",
+                e
+            ));
+        }
+    }
+
+    result
+}
+
+/// Report whether the user commands directory is writable, with the OS error if not
+///
+/// With `repair` set on Unix, tries to chmod the directory to user-writable. Ownership
+/// problems cannot be fixed this way and are reported as a failed repair.
+#[tauri::command]
+pub async fn check_commands_dir_writable(
+    repair: Option<bool>,
+) -> Result<CommandsDirWritability, CommandError> {
+    let dir = user_commands_dir()?;
+    let repair = repair.unwrap_or(false);
+    let result = tokio::task::spawn_blocking(move || check_dir_writable(&dir, repair))
+        .await
+        .map_err(|e| format!("Writability check failed: {}", e))?;
+
+    if !result.writable {
+        error!(
+            "Commands directory {} is not writable: {}",
+            result.path,
+            result.error.as_deref().unwrap_or("unknown error")
+        );
+    }
+    Ok(result)
+}
+
+/// Remove orphaned temp files from the user (and optionally project) commands directories
+#[tauri::command]
+pub async fn clean_command_temp_files(project_path: Option<String>) -> Result<usize, CommandError> {
+    let mut dirs_to_scan = Vec::new();
+
+    if let Some(proj_path) = project_path {
+        dirs_to_scan.push(PathBuf::from(proj_path).join(".claude").join("commands"));
+    }
+    if let Ok(user_dir) = user_commands_dir() {
+        dirs_to_scan.push(user_dir);
+    }
+
+    let cleaned: usize = dirs_to_scan
+        .iter()
+        .map(|dir| remove_stale_temp_files(dir))
+        .sum();
+
+    if cleaned > 0 {
+        info!("Cleaned {} orphaned command temp files", cleaned);
+    }
+    Ok(cleaned)
+}
+
+/// Remove empty directories recursively
+fn remove_empty_dirs(dir: &Path) -> Result<()> {
+    if !dir.exists() {
+        return Ok(());
+    }
+
+    // Check if directory is empty
+    let is_empty = fs::read_dir(dir)?.next().is_none();
+
+    if is_empty {
+        fs::remove_dir(dir)?;
+
+        // Try to remove parent if it's also empty
+        if let Some(parent) = dir.parent() {
+            let _ = remove_empty_dirs(parent);
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether `name` refers to `cmd` as "/ns:name", "ns:name" or just "name"
+fn matches_command_name(cmd: &SlashCommand, name: &str) -> bool {
+    let qualified = match &cmd.namespace {
+        Some(ns) => format!("{}:{}", ns, cmd.name),
+        None => cmd.name.clone(),
+    };
+    cmd.full_command == name || qualified == name || cmd.name == name
+}
+
+/// Number of unchanged lines shown around each change in a unified diff
+const DIFF_CONTEXT_LINES: usize = 3;
+
+/// Largest LCS table (old lines × new lines) `unified_diff` builds before summarizing instead
+const MAX_DIFF_CELLS: usize = 1_000_000;
+
+/// Produce a line-based unified diff from `old` to `new`; empty when they are identical
+///
+/// Inputs too large for the LCS table get a one-line "files differ" summary instead of hunks.
+fn unified_diff(old: &str, new: &str, old_label: &str, new_label: &str) -> String {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let (n, m) = (old_lines.len(), new_lines.len());
+
+    if old_lines == new_lines {
+        return String::new();
+    }
+    if n.saturating_mul(m) > MAX_DIFF_CELLS {
+        return format!(
+            "--- {}\n+++ {}\nFiles differ ({} and {} lines, too large to diff)\n",
+            old_label, new_label, n, m
+        );
+    }
+
+    // Longest common subsequence lengths of every suffix pair
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if old_lines[i] == new_lines[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    // Edit script of (tag, old index, new index)
+    let mut ops = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && old_lines[i] == new_lines[j] {
+            ops.push((' ', i, j));
+            i += 1;
+            j += 1;
+        } else if i < n && (j == m || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            ops.push(('-', i, j));
+            i += 1;
+        } else {
+            ops.push(('+', i, j));
+            j += 1;
+        }
+    }
+
+    if ops.iter().all(|(tag, _, _)| *tag == ' ') {
+        return String::new();
+    }
+
+    let mut diff = format!("--- {}\n+++ {}\n", old_label, new_label);
+    let mut next = 0;
+    while let Some(first_change) = ops[next..]
+        .iter()
+        .position(|(tag, _, _)| *tag != ' ')
+        .map(|p| p + next)
+    {
+        // Grow the hunk while changes are close enough to share context
+        let mut last_change = first_change;
+        for (idx, (tag, _, _)) in ops.iter().enumerate().skip(first_change) {
+            if *tag != ' ' {
+                last_change = idx;
+            } else if idx - last_change > 2 * DIFF_CONTEXT_LINES {
+                break;
+            }
+        }
+
+        let start = first_change.saturating_sub(DIFF_CONTEXT_LINES);
+        let end = (last_change + DIFF_CONTEXT_LINES + 1).min(ops.len());
+        let hunk = &ops[start..end];
+
+        let old_count = hunk.iter().filter(|(tag, _, _)| *tag != '+').count();
+        let new_count = hunk.iter().filter(|(tag, _, _)| *tag != '-').count();
+        let old_start = hunk[0].1 + usize::from(old_count > 0);
+        let new_start = hunk[0].2 + usize::from(new_count > 0);
+        diff.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            old_start, old_count, new_start, new_count
+        ));
+
+        for (tag, old_idx, new_idx) in hunk {
+            let line = if *tag == '+' {
+                new_lines[*new_idx]
+            } else {
+                old_lines[*old_idx]
+            };
+            diff.push(*tag);
+            diff.push_str(line);
+            diff.push('\n');
+        }
+
+        next = end;
+    }
+
+    diff
+}
+
+/// Unified diff between a stored command file and other content, e.g. from an import pack
+///
+/// A command that does not exist locally is treated as empty, so everything shows as added.
+#[tauri::command]
+pub async fn diff_command(
+    name: String,
+    other_content: String,
+    project_path: Option<String>,
+) -> Result<String, CommandError> {
+    debug!("Diffing slash command: {}", name);
+
+    let commands = slash_commands_list(project_path, None, None, None).await?;
+    let stored = commands
+        .into_iter()
+        .filter(|cmd| !cmd.file_path.is_empty())
+        .find(|cmd| matches_command_name(cmd, &name));
+
+    let (stored_label, stored_content) = match stored {
+        Some(cmd) => {
+            let content = fs::read_to_string(&cmd.file_path)
+                .map_err(|e| CommandError::Io(format!("Failed to read command file: {}", e)))?;
+            (cmd.file_path, content)
+        }
+        None => ("/dev/null".to_string(), String::new()),
+    };
+
+    Ok(unified_diff(
+        &stored_content,
+        &other_content,
+        &stored_label,
+        &format!("{} (incoming)", name),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_usage_boost_breaks_ties_between_equal_matches() {
+        let command = |name: &str| SlashCommand {
+            id: format!("user-{}", name),
+            name: name.to_string(),
+            full_command: format!("/{}", name),
+            scope: "user".to_string(),
+            namespace: None,
+            file_path: String::new(),
+            content: String::new(),
+            description: None,
+            allowed_tools: Vec::new(),
+            disallowed_tools: Vec::new(),
+            model: None,
+            has_bash_commands: false,
+            has_file_references: false,
+            accepts_arguments: false,
+            created_at: None,
+            modified_at: None,
+            tags: Vec::new(),
+        };
+        let commands = vec![
+            command("deploy-api"),
+            command("deploy-web"),
+            command("deploy"),
+        ];
+        let now = chrono::Utc::now();
+        let executions: Vec<CommandExecution> = (0..5)
+            .map(|_| CommandExecution {
+                name: "/deploy-web".to_string(),
+                project_path: "/tmp/project".to_string(),
+                model: "sonnet".to_string(),
+                args: None,
+                timestamp: now.to_rfc3339(),
+            })
+            .collect();
+        let names = |matches: Vec<SlashCommandMatch>| -> Vec<String> {
+            matches.into_iter().map(|m| m.command.name).collect()
+        };
+
+        let unboosted = rank_commands(commands.clone(), "deploy", None);
+        assert_eq!(names(unboosted), vec!["deploy", "deploy-api", "deploy-web"]);
+
+        // Usage reorders equal prefix matches but never beats the exact match
+        let boosts = usage_boosts(&executions, now);
+        let boosted = rank_commands(commands, "deploy", Some(&boosts));
+        assert_eq!(names(boosted), vec!["deploy", "deploy-web", "deploy-api"]);
+    }
+
+    #[test]
+    fn test_execution_history_is_newest_first_and_bounded() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let log_path = temp_dir.path().join(EXECUTION_LOG_FILE);
+        let execution = |n: usize| CommandExecution {
+            name: format!("/cmd-{}", n),
+            project_path: "/tmp/project".to_string(),
+            model: "sonnet".to_string(),
+            args: None,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        };
+
+        for n in 0..(MAX_EXECUTION_LOG_ENTRIES * 2 + 1) {
+            append_execution(&log_path, &execution(n)).unwrap();
+        }
+
+        let content = fs::read_to_string(&log_path).unwrap();
+        assert_eq!(content.lines().count(), MAX_EXECUTION_LOG_ENTRIES);
+
+        let recent = read_executions(&log_path, 2);
+        let names: Vec<&str> = recent.iter().map(|e| e.name.as_str()).collect();
+        let last = MAX_EXECUTION_LOG_ENTRIES * 2;
+        assert_eq!(
+            names,
+            vec![format!("/cmd-{}", last), format!("/cmd-{}", last - 1)]
+        );
+        assert!(read_executions(&temp_dir.path().join("missing"), 10).is_empty());
+    }
+
+    #[test]
+    fn test_tombstone_log_prunes_and_marks_truncation() {
+        let now = chrono::Utc::now();
+        let tombstone = |deleted_at: chrono::DateTime<chrono::Utc>, id: &str| CommandTombstone {
+            id: id.to_string(),
+            full_command: format!("/{}", id),
+            scope: "user".to_string(),
+            deleted_at: deleted_at.to_rfc3339(),
+        };
+
+        let old = now - chrono::Duration::days(45);
+        let mut log = TombstoneLog {
+            truncated_before: None,
+            entries: vec![tombstone(old, "old"), tombstone(now, "recent")],
+        };
+        log.prune(now);
+        assert_eq!(log.entries.len(), 1);
+        assert_eq!(log.entries[0].id, "recent");
+        assert_eq!(log.truncated_before, Some(old.to_rfc3339()));
+
+        let mut log = TombstoneLog {
+            truncated_before: None,
+            entries: (0..MAX_TOMBSTONES + 2)
+                .map(|i| tombstone(now, &format!("cmd{}", i)))
+                .collect(),
+        };
+        log.prune(now);
+        assert_eq!(log.entries.len(), MAX_TOMBSTONES);
+        assert_eq!(log.entries[0].id, "cmd2");
+        assert!(log.truncated_before.is_some());
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("tombstones").join("log.json");
+        log.save(&path).unwrap();
+        assert_eq!(
+            TombstoneLog::load(&path).unwrap().entries.len(),
+            MAX_TOMBSTONES
+        );
+    }
+
+    #[test]
+    fn test_quoted_frontmatter_round_trips() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("cmd.md");
+        let content = format!(
+            "---\ndescription: {}\ntags:\n  - {}\n  - {}\n---\n\nBody",
+            yaml_quote("Review: \"quoted\" text"),
+            yaml_quote("a: b"),
+            yaml_quote("#x"),
+        );
+        fs::write(&path, &content).unwrap();
+
+        let (fm, body) = parse_markdown_with_frontmatter(&content).unwrap();
+        let fm = fm.expect("frontmatter should parse");
+        assert_eq!(fm.description.as_deref(), Some("Review: \"quoted\" text"));
+        assert_eq!(body.trim(), "Body");
+        assert_eq!(
+            existing_command_tags(&path),
+            Some(vec!["a: b".to_string(), "#x".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_temp_file_names_match_exactly() {
+        assert!(is_atomic_write_temp("deploy.md.tmp.4242"));
+        assert!(is_atomic_write_temp(".command-history.jsonl.tmp.1"));
+        assert!(!is_atomic_write_temp("notes.tmp.md"));
+        assert!(!is_atomic_write_temp("notes.tmp."));
+        assert!(!is_atomic_write_temp(".tmp.12"));
+        assert!(!is_atomic_write_temp(".write-probe.tmp.12"));
+
+        assert!(is_write_probe_temp(".write-probe.tmp.12"));
+        assert!(!is_write_probe_temp(".write-probe.tmp.md"));
+        assert!(!is_write_probe_temp("x.write-probe.tmp.12"));
+    }
+
+    #[test]
+    fn test_validate_export_path() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let target = temp_dir.path().join("commands.json");
+
+        assert_eq!(
+            validate_export_path(target.to_str().unwrap()).unwrap(),
+            target
+        );
+        assert!(validate_export_path("commands.json").is_err());
+        assert!(validate_export_path(temp_dir.path().to_str().unwrap()).is_err());
+        assert!(validate_export_path(
+            temp_dir
+                .path()
+                .join("missing")
+                .join("commands.json")
+                .to_str()
+                .unwrap()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_check_dir_writable_reports_os_error() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+
+        let ok = check_dir_writable(&temp_dir.path().join("commands"), false);
+        assert!(ok.writable);
+        assert!(ok.error.is_none());
+        assert!(fs::read_dir(temp_dir.path().join("commands"))
+            .unwrap()
+            .next()
+            .is_none());
+
+        // A file where a parent directory should be can never be made writable
+        let blocker = temp_dir.path().join("blocker");
+        fs::write(&blocker, "x").unwrap();
+        let failed = check_dir_writable(&blocker.join("commands"), true);
+        assert!(!failed.writable);
+        assert!(!failed.repaired);
+        assert!(failed.error.is_some());
+    }
+
+    #[test]
+    fn test_resolve_commands_dir_with_override() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let custom = temp_dir.path().join("relocated").join("commands");
+
+        let resolved = resolve_commands_dir(Some(custom.to_str().unwrap())).unwrap();
+        assert_eq!(resolved, custom);
+        assert!(custom.is_dir());
+
+        let file = temp_dir.path().join("not-a-dir");
+        fs::write(&file, "x").unwrap();
+        assert!(resolve_commands_dir(Some(file.to_str().unwrap())).is_err());
+        assert!(resolve_commands_dir(Some("relative/commands")).is_err());
+
+        let default = resolve_commands_dir(Some("  ")).unwrap();
+        assert!(default.ends_with(".claude/commands"));
+    }
+
+    #[test]
+    fn test_normalize_command_name() {
+        assert_eq!(normalize_command_name("Review PR"), "review-pr");
+        assert_eq!(
+            normalize_command_name("  fix   Lint  errors "),
+            "fix-lint-errors"
+        );
+        assert_eq!(normalize_command_name("already-kebab"), "already-kebab");
+        assert_eq!(normalize_command_name("Mixed_Case Name"), "mixed_case-name");
+        assert_eq!(
+            normalize_command_name("trailing - hyphen -"),
+            "trailing-hyphen"
+        );
+        assert_eq!(normalize_command_name("   "), "");
+    }
+
+    #[test]
+    fn test_loaded_commands_always_have_timestamps() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let base = temp_dir.path();
+
+        let plain = base.join("plain.md");
+        fs::write(&plain, "Do the thing").unwrap();
+        let cmd = load_command_from_file(&plain, base, "user").unwrap();
+        assert!(cmd.created_at.is_some());
+        assert!(cmd.modified_at.is_some());
+
+        let stamped = base.join("stamped.md");
+        fs::write(
+            &stamped,
+            "---\ncreated_at: \"2024-01-01T00:00:00+00:00\"\n---\n\nBody",
+        )
+        .unwrap();
+        let cmd = load_command_from_file(&stamped, base, "user").unwrap();
+        assert_eq!(cmd.created_at.as_deref(), Some("2024-01-01T00:00:00+00:00"));
+        assert!(cmd.modified_at.is_some());
+    }
+
+    #[test]
+    fn test_sidecar_metadata_merges_under_frontmatter() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("deploy.md");
+        fs::write(
+            &file_path,
+            "---\ndescription: From frontmatter\n---\n\nDeploy it",
+        )
+        .unwrap();
+        fs::write(
+            command_sidecar_path(&file_path),
+            r#"{"description": "From sidecar", "model": "opus", "tags": ["ops"]}"#,
+        )
+        .unwrap();
+
+        let command = load_command_from_file(&file_path, dir.path(), "user").unwrap();
+        assert_eq!(command.description.as_deref(), Some("From frontmatter"));
+        assert_eq!(command.model.as_deref(), Some("opus"));
+        assert_eq!(command.tags, vec!["ops"]);
+
+        let mut md_files = Vec::new();
+        find_markdown_files(dir.path(), &mut md_files).unwrap();
+        assert_eq!(md_files, vec![file_path]);
+    }
+
+    #[test]
+    fn test_has_all_tags() {
+        let mut command = create_default_commands().remove(0);
+        command.tags = vec!["Ops".to_string(), "deploy".to_string()];
+
+        assert!(has_all_tags(&command, &[]));
+        assert!(has_all_tags(&command, &["ops".to_string()]));
+        assert!(has_all_tags(
+            &command,
+            &["deploy".to_string(), "OPS".to_string()]
+        ));
+        assert!(!has_all_tags(
+            &command,
+            &["ops".to_string(), "review".to_string()]
+        ));
+    }
+
+    #[test]
+    fn test_normalized_content_hash() {
+        let hash = normalized_content_hash("Review the diff\nCarefully");
+        assert_eq!(
+            normalized_content_hash("\nReview the diff  \r\nCarefully\n\n"),
+            hash
+        );
+        assert_ne!(normalized_content_hash("Review the diff\ncarefully"), hash);
+    }
+
+    #[test]
+    fn test_command_error_serializes_code() {
+        let error = CommandError::NotFound("Command not found: x".to_string());
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({"code": "not_found", "message": "Command not found: x"})
+        );
+        assert_eq!(error.code(), "not_found");
+
+        let error = CommandError::AlreadyExists("Command already exists: x".to_string());
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({"code": "already_exists", "message": "Command already exists: x"})
+        );
+    }
+
+    #[test]
+    fn test_command_bundle_round_trip() {
+        let commands = vec![
+            ExportedCommand {
+                name: "review".to_string(),
+                namespace: None,
+                content: "Review $ARGUMENTS".to_string(),
+                scope: None,
+            },
+            ExportedCommand {
+                name: "component".to_string(),
+                namespace: Some("frontend".to_string()),
+                content: "---\ndescription: New component\n---\n\nBody".to_string(),
+                scope: Some("project".to_string()),
+            },
+        ];
+
+        for compress in [false, true] {
+            let bytes = encode_command_bundle(&commands, compress).unwrap();
+            assert_eq!(bytes.starts_with(&GZIP_MAGIC), compress);
+
+            let decoded = decode_command_bundle(&bytes).unwrap();
+            assert_eq!(decoded.len(), commands.len());
+            for (a, b) in decoded.iter().zip(&commands) {
+                assert_eq!(a.name, b.name);
+                assert_eq!(a.namespace, b.namespace);
+                assert_eq!(a.content, b.content);
+                assert_eq!(a.scope, b.scope);
+            }
+        }
+    }
+
+    #[test]
+    fn test_unified_diff() {
+        assert_eq!(unified_diff("a\nb\n", "a\nb\n", "old", "new"), "");
+
+        let diff = unified_diff("a\nb\nc\n", "a\nB\nc\n", "old", "new");
+        assert_eq!(diff, "--- old\n+++ new\n@@ -1,3 +1,3 @@\n a\n-b\n+B\n c\n");
+
+        let added = unified_diff("", "x\ny\n", "/dev/null", "new");
+        assert_eq!(added, "--- /dev/null\n+++ new\n@@ -0,0 +1,2 @@\n+x\n+y\n");
+    }
+
+    #[test]
+    fn test_unified_diff_summarizes_large_inputs() {
+        let old = "a\n".repeat(1_500);
+        let new = "b\n".repeat(1_500);
+        assert_eq!(
+            unified_diff(&old, &new, "old", "new"),
+            "--- old\n+++ new\nFiles differ (1500 and 1500 lines, too large to diff)\n"
+        );
+        assert_eq!(unified_diff(&old, &old, "old", "new"), "");
+    }
+
+    #[test]
+    fn test_normalized_names_are_valid_segments() {
+        for input in ["Review PR", "deploy to Prod", "v2 release notes"] {
+            assert!(is_valid_command_segment(&normalize_command_name(input)));
+        }
+        assert!(!is_valid_command_segment(&normalize_command_name(
+            "../escape attempt"
+        )));
+    }
 }
@@ -1,9 +1,12 @@
-use super::agents::AgentDb;
+use super::agents::{run_migrations, schema_version, AgentDb, MIGRATIONS};
 use anyhow::Result;
-use rusqlite::{params, types::ValueRef, Connection, Result as SqliteResult};
+use rusqlite::{
+    params, types::ValueRef, Connection, DatabaseName, OpenFlags, Result as SqliteResult,
+};
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value as JsonValue};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use tauri::{AppHandle, Manager, State};
 
 /// Represents metadata about a database table
@@ -494,6 +497,544 @@ pub async fn storage_reset_database(app: AppHandle) -> Result<(), String> {
     Ok(())
 }
 
+/// Copy the live agents database using SQLite's online backup API
+///
+/// Safe to run while the app is using the database. Without `dest` the copy is
+/// written to `backups/` in the app data directory. Returns the backup path.
+#[tauri::command]
+pub async fn backup_agents_db(
+    app: AppHandle,
+    db: State<'_, AgentDb>,
+    dest: Option<String>,
+) -> Result<String, String> {
+    let dest = match dest {
+        Some(dest) => PathBuf::from(dest),
+        None => {
+            let backups_dir = app_data_dir(&app)?.join("backups");
+            std::fs::create_dir_all(&backups_dir)
+                .map_err(|e| format!("Failed to create backups directory: {}", e))?;
+            backups_dir.join(format!(
+                "agents-{}.db",
+                chrono::Utc::now().format("%Y%m%d-%H%M%S")
+            ))
+        }
+    };
+
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    conn.backup(DatabaseName::Main, &dest, None)
+        .map_err(|e| format!("Failed to back up database: {}", e))?;
+
+    log::info!("Backed up agents database to {:?}", dest);
+    Ok(dest.to_string_lossy().to_string())
+}
+
+/// Check that a file is an opcode agents database this build can open
+fn validate_agents_db_file(path: &Path) -> Result<i64, String> {
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(|e| format!("Failed to open backup: {}", e))?;
+
+    for table in ["agents", "agent_runs", "app_settings"] {
+        if !is_valid_table_name(&conn, table)? {
+            return Err(format!("Not an opcode database: missing table {}", table));
+        }
+    }
+
+    let version = schema_version(&conn);
+    if version < 1 {
+        return Err("Not an opcode database: no schema_version recorded".to_string());
+    }
+    if version > MIGRATIONS.len() as i64 {
+        return Err(format!(
+            "Backup was made by a newer opcode (schema version {})",
+            version
+        ));
+    }
+    Ok(version)
+}
+
+/// Number of agent runs that are running, rate limited or queued
+fn count_active_runs(conn: &Connection) -> Result<i64, String> {
+    conn.query_row(
+        "SELECT COUNT(*) FROM agent_runs WHERE status IN ('running', 'rate_limited', 'queued')",
+        [],
+        |row| row.get(0),
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Replace the live agents database with a validated backup
+///
+/// Older backups are migrated to the current schema after restoring. Refuses while
+/// agent runs are active, since they would record their status in the replaced database.
+#[tauri::command]
+pub async fn restore_agents_db(db: State<'_, AgentDb>, src: String) -> Result<(), String> {
+    let src_path = PathBuf::from(&src);
+    let version = validate_agents_db_file(&src_path)?;
+
+    let mut conn = db.0.lock().map_err(|e| e.to_string())?;
+    let active = count_active_runs(&conn)?;
+    if active > 0 {
+        return Err(format!(
+            "Cannot restore the database while {} agent runs are active",
+            active
+        ));
+    }
+    conn.restore(
+        DatabaseName::Main,
+        &src_path,
+        None::<fn(rusqlite::backup::Progress)>,
+    )
+    .map_err(|e| format!("Failed to restore database: {}", e))?;
+    run_migrations(&conn).map_err(|e| format!("Failed to migrate restored database: {}", e))?;
+
+    log::info!(
+        "Restored agents database from {} (schema version {})",
+        src,
+        version
+    );
+    Ok(())
+}
+
+/// Disk usage of opcode's data, broken down by category
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct StorageUsage {
+    /// Bytes used per category: commands, checkpoints, session_logs,
+    /// agent_runs, database, backups and cache
+    pub categories: HashMap<String, u64>,
+    /// Sum of all categories
+    pub total_bytes: u64,
+    /// Entries that could not be read (permissions, races with deletion)
+    pub skipped_entries: u64,
+}
+
+/// Deepest directory level walked when measuring usage
+const MAX_USAGE_DEPTH: usize = 32;
+
+/// Sum file sizes under `path` without following symlinks
+///
+/// `skip_dir` prunes directories by name. Unreadable entries are counted in
+/// `skipped` rather than failing the whole walk.
+fn dir_size(path: &Path, depth: usize, skip_dir: Option<&str>, skipped: &mut u64) -> u64 {
+    let metadata = match std::fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return 0,
+        Err(_) => {
+            *skipped += 1;
+            return 0;
+        }
+    };
+    if metadata.is_file() {
+        return metadata.len();
+    }
+    if !metadata.is_dir() || depth >= MAX_USAGE_DEPTH {
+        return 0;
+    }
+
+    let entries = match std::fs::read_dir(path) {
+        Ok(entries) => entries,
+        Err(_) => {
+            *skipped += 1;
+            return 0;
+        }
+    };
+
+    let mut total = 0;
+    for entry in entries {
+        match entry {
+            Ok(entry) => {
+                let entry_path = entry.path();
+                if skip_dir.is_some_and(|name| entry.file_name() == name) && entry_path.is_dir() {
+                    continue;
+                }
+                total += dir_size(&entry_path, depth + 1, skip_dir, skipped);
+            }
+            Err(_) => *skipped += 1,
+        }
+    }
+    total
+}
+
+/// Report how much disk space opcode's data uses
+///
+/// Checkpoints are the `.timelines` directories under `~/.claude/projects`;
+/// `session_logs` covers the rest of that directory (session JSONL files).
+#[tauri::command]
+pub async fn get_storage_usage(app: AppHandle) -> Result<StorageUsage, String> {
+    let claude_dir = dirs::home_dir()
+        .ok_or_else(|| "Could not find home directory".to_string())?
+        .join(".claude");
+    let app_data_dir = app_data_dir(&app)?;
+    let app_cache_dir = app.path().app_cache_dir().ok();
+    let commands_dir = super::slash_commands::user_commands_dir()?;
+
+    tokio::task::spawn_blocking(move || {
+        let mut skipped = 0;
+        let mut categories = HashMap::new();
+
+        categories.insert(
+            "commands".to_string(),
+            dir_size(&commands_dir, 0, None, &mut skipped),
+        );
+
+        let projects_dir = claude_dir.join("projects");
+        let mut checkpoints = 0;
+        if let Ok(entries) = std::fs::read_dir(&projects_dir) {
+            for entry in entries.flatten() {
+                checkpoints += dir_size(&entry.path().join(".timelines"), 1, None, &mut skipped);
+            }
+        }
+        categories.insert("checkpoints".to_string(), checkpoints);
+        categories.insert(
+            "session_logs".to_string(),
+            dir_size(&projects_dir, 0, Some(".timelines"), &mut skipped),
+        );
+
+        categories.insert(
+            "agent_runs".to_string(),
+            dir_size(&app_data_dir.join("agent_runs"), 0, None, &mut skipped),
+        );
+        let database = ["agents.db", "agents.db-wal", "agents.db-shm"]
+            .iter()
+            .map(|name| dir_size(&app_data_dir.join(name), 0, None, &mut skipped))
+            .sum();
+        categories.insert("database".to_string(), database);
+        categories.insert(
+            "backups".to_string(),
+            dir_size(&app_data_dir.join("backups"), 0, None, &mut skipped),
+        );
+        categories.insert(
+            "cache".to_string(),
+            app_cache_dir
+                .map(|dir| dir_size(&dir, 0, None, &mut skipped))
+                .unwrap_or(0),
+        );
+
+        let total_bytes = categories.values().sum();
+        StorageUsage {
+            categories,
+            total_bytes,
+            skipped_entries: skipped,
+        }
+    })
+    .await
+    .map_err(|e| format!("Failed to compute storage usage: {}", e))
+}
+
+/// Bootstrap file in the app config directory pointing at a relocated data directory
+///
+/// It cannot live in the agents database, since the database moves with the data.
+const DATA_DIR_POINTER_FILE: &str = "data-directory.json";
+
+/// Database files copied with SQLite's backup API rather than as plain files
+const AGENTS_DB_FILES: [&str; 3] = ["agents.db", "agents.db-wal", "agents.db-shm"];
+
+/// Headroom required on the target volume beyond the size of the data
+const DATA_DIR_FREE_SPACE_MARGIN: u64 = 64 * 1024 * 1024;
+
+/// Resolved data directory; `None` until first use
+static DATA_DIR: std::sync::Mutex<Option<PathBuf>> = std::sync::Mutex::new(None);
+
+/// Contents of the data directory pointer file
+#[derive(Debug, Serialize, Deserialize)]
+struct DataDirPointer {
+    path: PathBuf,
+}
+
+fn data_dir_pointer_path(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(app
+        .path()
+        .app_config_dir()
+        .map_err(|e| format!("Failed to get app config dir: {}", e))?
+        .join(DATA_DIR_POINTER_FILE))
+}
+
+/// Read the relocated data directory, if one is configured and still usable
+fn read_data_dir_pointer(pointer_path: &Path) -> Option<PathBuf> {
+    let content = std::fs::read_to_string(pointer_path).ok()?;
+    match serde_json::from_str::<DataDirPointer>(&content) {
+        Ok(pointer) if pointer.path.is_dir() => {
+            log::info!("Using relocated data directory: {:?}", pointer.path);
+            Some(pointer.path)
+        }
+        Ok(pointer) => {
+            log::error!(
+                "Relocated data directory {:?} is missing, using the default location",
+                pointer.path
+            );
+            None
+        }
+        Err(e) => {
+            log::error!("Ignoring invalid {}: {}", DATA_DIR_POINTER_FILE, e);
+            None
+        }
+    }
+}
+
+/// Directory holding opcode's data: the agents database, run output and backups
+///
+/// This is Tauri's app data directory unless `set_data_directory` moved it.
+/// Everything that stores data there should resolve it through this function.
+pub fn app_data_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let mut cached = DATA_DIR.lock().map_err(|e| e.to_string())?;
+    if let Some(dir) = cached.as_ref() {
+        return Ok(dir.clone());
+    }
+
+    let pointer = data_dir_pointer_path(app)
+        .ok()
+        .and_then(|path| read_data_dir_pointer(&path));
+    let dir = match pointer {
+        Some(dir) => dir,
+        None => app
+            .path()
+            .app_data_dir()
+            .map_err(|e| format!("Failed to get app data dir: {}", e))?,
+    };
+    *cached = Some(dir.clone());
+    Ok(dir)
+}
+
+/// Tauri bundle identifier, which names the platform app config and data directories
+const APP_IDENTIFIER: &str = "opcode.asterisk.so";
+
+/// `app_data_dir` for code that has no `AppHandle`, such as the web server
+///
+/// Mirrors Tauri's directory layout, so the pointer written by the desktop app
+/// is honoured here too.
+pub fn current_app_data_dir() -> Result<PathBuf, String> {
+    let mut cached = DATA_DIR.lock().map_err(|e| e.to_string())?;
+    if let Some(dir) = cached.as_ref() {
+        return Ok(dir.clone());
+    }
+
+    let config_dir = dirs::config_dir()
+        .ok_or_else(|| "Could not find config directory".to_string())?
+        .join(APP_IDENTIFIER);
+    let default_dir = dirs::data_dir()
+        .ok_or_else(|| "Could not find data directory".to_string())?
+        .join(APP_IDENTIFIER);
+    let dir = resolve_data_dir(&config_dir, default_dir);
+    *cached = Some(dir.clone());
+    Ok(dir)
+}
+
+/// The data directory named by the pointer in `config_dir`, or `default_dir`
+fn resolve_data_dir(config_dir: &Path, default_dir: PathBuf) -> PathBuf {
+    read_data_dir_pointer(&config_dir.join(DATA_DIR_POINTER_FILE)).unwrap_or(default_dir)
+}
+
+/// Free bytes available to this user on the volume holding `path`, via `df`
+#[cfg(unix)]
+fn available_space(path: &Path) -> Option<u64> {
+    let output = std::process::Command::new("df")
+        .arg("-Pk")
+        .arg(path)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_df_available(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Parse available bytes from `df -Pk` output
+///
+/// POSIX output is a header line, then one line per filesystem with the available
+/// 1024-byte blocks in the fourth column.
+#[cfg(unix)]
+fn parse_df_available(output: &str) -> Option<u64> {
+    let line = output.lines().nth(1)?;
+    let blocks: u64 = line.split_whitespace().nth(3)?.parse().ok()?;
+    Some(blocks.saturating_mul(1024))
+}
+
+/// Free space is not checked on this platform
+#[cfg(not(unix))]
+fn available_space(_path: &Path) -> Option<u64> {
+    None
+}
+
+/// Check that `target` can receive `needed` bytes of data
+///
+/// The directory is created if missing and must be empty, writable and, where the
+/// platform reports it, have enough free space.
+fn validate_data_dir_target(target: &Path, needed: u64) -> Result<(), String> {
+    std::fs::create_dir_all(target)
+        .map_err(|e| format!("Failed to create {}: {}", target.display(), e))?;
+    let has_entries = std::fs::read_dir(target)
+        .map_err(|e| format!("Failed to read {}: {}", target.display(), e))?
+        .next()
+        .is_some();
+    if has_entries {
+        return Err(format!("{} is not empty", target.display()));
+    }
+
+    let probe = target.join(".opcode-write-test");
+    std::fs::write(&probe, b"ok")
+        .map_err(|e| format!("{} is not writable: {}", target.display(), e))?;
+    let _ = std::fs::remove_file(&probe);
+
+    match available_space(target) {
+        Some(available) if available < needed.saturating_add(DATA_DIR_FREE_SPACE_MARGIN) => {
+            Err(format!(
+                "Not enough space in {}: {} bytes needed, {} available",
+                target.display(),
+                needed,
+                available
+            ))
+        }
+        Some(_) => Ok(()),
+        None => {
+            log::warn!("Could not determine free space in {:?}", target);
+            Ok(())
+        }
+    }
+}
+
+/// Copy the data directory's files, skipping the database and the pointer file
+///
+/// Each copy is checked against the source size. Returns the number of files
+/// and bytes copied.
+fn copy_data_files(source: &Path, target: &Path) -> Result<(u64, u64), String> {
+    let mut files = 0;
+    let mut bytes = 0;
+    for entry in walkdir::WalkDir::new(source).min_depth(1) {
+        let entry = entry.map_err(|e| format!("Failed to read data directory: {}", e))?;
+        let relative = entry
+            .path()
+            .strip_prefix(source)
+            .map_err(|e| e.to_string())?;
+        if entry.depth() == 1
+            && entry.file_name().to_str().is_some_and(|name| {
+                AGENTS_DB_FILES.contains(&name) || name == DATA_DIR_POINTER_FILE
+            })
+        {
+            continue;
+        }
+
+        let dest = target.join(relative);
+        if entry.file_type().is_dir() {
+            std::fs::create_dir_all(&dest)
+                .map_err(|e| format!("Failed to create {}: {}", dest.display(), e))?;
+        } else if entry.file_type().is_file() {
+            let copied = std::fs::copy(entry.path(), &dest)
+                .map_err(|e| format!("Failed to copy {}: {}", relative.display(), e))?;
+            let expected = entry.metadata().map_err(|e| e.to_string())?.len();
+            if copied != expected {
+                return Err(format!(
+                    "Copy of {} is incomplete: {} of {} bytes",
+                    relative.display(),
+                    copied,
+                    expected
+                ));
+            }
+            files += 1;
+            bytes += copied;
+        }
+    }
+    Ok((files, bytes))
+}
+
+/// Result of moving the data directory
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DataDirectoryMove {
+    pub previous: String,
+    pub current: String,
+    /// Files copied besides the agents database
+    pub files_copied: u64,
+    pub bytes_copied: u64,
+}
+
+/// Get the directory opcode stores its database, run output and backups in
+#[tauri::command]
+pub async fn get_data_directory(app: AppHandle) -> Result<String, String> {
+    Ok(app_data_dir(&app)?.to_string_lossy().to_string())
+}
+
+/// Move opcode's data to a new directory
+///
+/// The target must be an empty (or new) writable directory with room for the
+/// data. Files are copied, the database through SQLite's backup API, and verified
+/// before the pointer is switched and the database reopened from the new place.
+/// The old directory is left in place so it can be removed once the move is
+/// confirmed. Refused while agent runs are active.
+#[tauri::command]
+pub async fn set_data_directory(
+    app: AppHandle,
+    db: State<'_, AgentDb>,
+    path: String,
+) -> Result<DataDirectoryMove, String> {
+    let path = path.trim();
+    let target = match path.strip_prefix("~/") {
+        Some(rest) => dirs::home_dir()
+            .ok_or_else(|| "Could not find home directory".to_string())?
+            .join(rest),
+        None => PathBuf::from(path),
+    };
+    if !target.is_absolute() {
+        return Err("Data directory must be an absolute path".to_string());
+    }
+
+    let current = app_data_dir(&app)?;
+    if target.starts_with(&current) || current.starts_with(&target) {
+        return Err(format!(
+            "{} overlaps the current data directory {}",
+            target.display(),
+            current.display()
+        ));
+    }
+
+    // Hold the database lock for the whole move so nothing writes to the old copy
+    let mut conn = db.0.lock().map_err(|e| e.to_string())?;
+    let active = count_active_runs(&conn)?;
+    if active > 0 {
+        return Err(format!(
+            "Cannot move the data directory while {} agent runs are active",
+            active
+        ));
+    }
+
+    let mut skipped = 0;
+    validate_data_dir_target(&target, dir_size(&current, 0, None, &mut skipped))?;
+
+    let (files_copied, bytes_copied) = copy_data_files(&current, &target)?;
+    let new_db_path = target.join("agents.db");
+    conn.backup(DatabaseName::Main, &new_db_path, None)
+        .map_err(|e| format!("Failed to copy database: {}", e))?;
+    validate_agents_db_file(&new_db_path)?;
+
+    let pointer_path = data_dir_pointer_path(&app)?;
+    if let Some(parent) = pointer_path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create app config dir: {}", e))?;
+    }
+    let pointer = serde_json::to_string_pretty(&DataDirPointer {
+        path: target.clone(),
+    })
+    .map_err(|e| e.to_string())?;
+    let tmp_path = pointer_path.with_extension("json.tmp");
+    std::fs::write(&tmp_path, pointer)
+        .and_then(|_| std::fs::rename(&tmp_path, &pointer_path))
+        .map_err(|e| format!("Failed to write {}: {}", DATA_DIR_POINTER_FILE, e))?;
+
+    *DATA_DIR.lock().map_err(|e| e.to_string())? = Some(target.clone());
+    *conn = init_database(&app).map_err(|e| format!("Failed to open moved database: {}", e))?;
+
+    log::info!(
+        "Moved data directory from {:?} to {:?} ({} files, {} bytes)",
+        current,
+        target,
+        files_copied,
+        bytes_copied
+    );
+    Ok(DataDirectoryMove {
+        previous: current.to_string_lossy().to_string(),
+        current: target.to_string_lossy().to_string(),
+        files_copied,
+        bytes_copied,
+    })
+}
+
 /// Helper function to validate table name exists
 fn is_valid_table_name(conn: &Connection, table_name: &str) -> Result<bool, String> {
     let count: i64 = conn
@@ -528,3 +1069,119 @@ fn json_to_sql_value(value: &JsonValue) -> Result<Box<dyn rusqlite::ToSql>, Stri
 
 /// Initialize the agents database (re-exported from agents module)
 use super::agents::init_database;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_parse_df_available() {
+        let output = "Filesystem     1024-blocks      Used Available Capacity Mounted on\n\
+                      /dev/disk1s1     488245288 401234567  87010721      83% /\n";
+        assert_eq!(parse_df_available(output), Some(87010721 * 1024));
+
+        assert_eq!(parse_df_available(""), None);
+        assert_eq!(
+            parse_df_available("Filesystem 1024-blocks Used Available Capacity Mounted on\n"),
+            None
+        );
+        assert_eq!(
+            parse_df_available("header\n/dev/sda1 100 50 n/a 50% /\n"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_validate_agents_db_file() {
+        let temp_dir = TempDir::new().unwrap();
+
+        let valid_path = temp_dir.path().join("valid.db");
+        let conn = Connection::open(&valid_path).unwrap();
+        run_migrations(&conn).unwrap();
+        drop(conn);
+        assert_eq!(
+            validate_agents_db_file(&valid_path),
+            Ok(MIGRATIONS.len() as i64)
+        );
+
+        let unversioned_path = temp_dir.path().join("unversioned.db");
+        let conn = Connection::open(&unversioned_path).unwrap();
+        run_migrations(&conn).unwrap();
+        conn.execute("DELETE FROM app_settings WHERE key = 'schema_version'", [])
+            .unwrap();
+        drop(conn);
+        let err = validate_agents_db_file(&unversioned_path).unwrap_err();
+        assert!(err.contains("schema_version"), "{}", err);
+
+        let foreign_path = temp_dir.path().join("foreign.db");
+        let conn = Connection::open(&foreign_path).unwrap();
+        conn.execute("CREATE TABLE notes (id INTEGER PRIMARY KEY)", [])
+            .unwrap();
+        drop(conn);
+        let err = validate_agents_db_file(&foreign_path).unwrap_err();
+        assert!(err.contains("missing table agents"), "{}", err);
+    }
+
+    #[test]
+    fn test_count_active_runs() {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+        assert_eq!(count_active_runs(&conn), Ok(0));
+
+        for (id, status) in ["completed", "running", "queued", "failed"]
+            .iter()
+            .enumerate()
+        {
+            conn.execute(
+                "INSERT INTO agent_runs (id, agent_name, agent_icon, task, model, project_path, session_id, status)
+                 VALUES (?1, 'agent', 'bot', 'task', 'sonnet', '/tmp', '', ?2)",
+                params![id as i64 + 1, status],
+            )
+            .unwrap();
+        }
+        assert_eq!(count_active_runs(&conn), Ok(2));
+    }
+
+    #[test]
+    fn test_resolve_data_dir_uses_pointer() {
+        let config_dir = TempDir::new().unwrap();
+        let relocated = TempDir::new().unwrap();
+        let default_dir = PathBuf::from("/nonexistent/default");
+
+        assert_eq!(
+            resolve_data_dir(config_dir.path(), default_dir.clone()),
+            default_dir
+        );
+
+        let pointer = serde_json::to_string(&DataDirPointer {
+            path: relocated.path().to_path_buf(),
+        })
+        .unwrap();
+        std::fs::write(config_dir.path().join(DATA_DIR_POINTER_FILE), pointer).unwrap();
+        assert_eq!(
+            resolve_data_dir(config_dir.path(), default_dir.clone()),
+            relocated.path()
+        );
+    }
+
+    #[test]
+    fn test_resolve_data_dir_ignores_unusable_pointer() {
+        let config_dir = TempDir::new().unwrap();
+        let default_dir = PathBuf::from("/nonexistent/default");
+        let pointer_path = config_dir.path().join(DATA_DIR_POINTER_FILE);
+
+        std::fs::write(&pointer_path, "not json").unwrap();
+        assert_eq!(
+            resolve_data_dir(config_dir.path(), default_dir.clone()),
+            default_dir
+        );
+
+        std::fs::write(&pointer_path, r#"{"path": "/nonexistent/relocated"}"#).unwrap();
+        assert_eq!(
+            resolve_data_dir(config_dir.path(), default_dir.clone()),
+            default_dir
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use chrono::{DateTime, Local, NaiveDate};
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate};
 use serde::{Deserialize, Serialize};
 use serde_json;
 use std::collections::{HashMap, HashSet};
@@ -63,6 +63,29 @@ pub struct ProjectUsage {
     last_used: String,
 }
 
+/// Granularity of a usage timeseries bucket
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum UsageBucket {
+    Day,
+    Week,
+    Month,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UsageTimeseriesBucket {
+    /// First day covered by the bucket (YYYY-MM-DD), clamped to the requested range
+    bucket_start: String,
+    /// Last day covered by the bucket, clamped to the requested range
+    bucket_end: String,
+    total_cost: f64,
+    total_tokens: u64,
+    by_model: Vec<ModelUsage>,
+}
+
+/// Upper bound on buckets returned by a single timeseries query
+const MAX_TIMESERIES_BUCKETS: usize = 3660;
+
 // Claude 4 pricing constants (per million tokens)
 const OPUS_4_INPUT_PRICE: f64 = 15.0;
 const OPUS_4_OUTPUT_PRICE: f64 = 75.0;
@@ -74,6 +97,18 @@ const SONNET_4_OUTPUT_PRICE: f64 = 15.0;
 const SONNET_4_CACHE_WRITE_PRICE: f64 = 3.75;
 const SONNET_4_CACHE_READ_PRICE: f64 = 0.30;
 
+/// Returns the (input, output) price per million tokens for a model name or alias
+pub fn model_token_prices(model: &str) -> Option<(f64, f64)> {
+    let model = model.to_lowercase();
+    if model.contains("opus") {
+        Some((OPUS_4_INPUT_PRICE, OPUS_4_OUTPUT_PRICE))
+    } else if model.contains("sonnet") {
+        Some((SONNET_4_INPUT_PRICE, SONNET_4_OUTPUT_PRICE))
+    } else {
+        None
+    }
+}
+
 #[derive(Debug, Deserialize)]
 struct JsonlEntry {
     timestamp: String,
@@ -348,25 +383,7 @@ pub fn get_usage_stats(days: Option<u32>) -> Result<UsageStats, String> {
         total_cache_read_tokens += entry.cache_read_tokens;
 
         // Update model stats
-        let model_stat = model_stats
-            .entry(entry.model.clone())
-            .or_insert(ModelUsage {
-                model: entry.model.clone(),
-                total_cost: 0.0,
-                total_tokens: 0,
-                input_tokens: 0,
-                output_tokens: 0,
-                cache_creation_tokens: 0,
-                cache_read_tokens: 0,
-                session_count: 0,
-            });
-        model_stat.total_cost += entry.cost;
-        model_stat.input_tokens += entry.input_tokens;
-        model_stat.output_tokens += entry.output_tokens;
-        model_stat.cache_creation_tokens += entry.cache_creation_tokens;
-        model_stat.cache_read_tokens += entry.cache_read_tokens;
-        model_stat.total_tokens = model_stat.input_tokens + model_stat.output_tokens;
-        model_stat.session_count += 1;
+        accumulate_model_usage(&mut model_stats, entry);
 
         // Update daily stats
         let date = entry
@@ -448,30 +465,23 @@ pub fn get_usage_stats(days: Option<u32>) -> Result<UsageStats, String> {
     })
 }
 
-#[command]
-pub fn get_usage_by_date_range(start_date: String, end_date: String) -> Result<UsageStats, String> {
-    let claude_path = dirs::home_dir()
-        .ok_or("Failed to get home directory")?
-        .join(".claude");
-
-    let all_entries = get_all_usage_entries(&claude_path);
-
-    // Parse dates
-    let start = NaiveDate::parse_from_str(&start_date, "%Y-%m-%d").or_else(|_| {
-        // Try parsing ISO datetime format
-        DateTime::parse_from_rfc3339(&start_date)
-            .map(|dt| dt.naive_local().date())
-            .map_err(|e| format!("Invalid start date: {}", e))
-    })?;
-    let end = NaiveDate::parse_from_str(&end_date, "%Y-%m-%d").or_else(|_| {
+/// Parses a YYYY-MM-DD or RFC 3339 date used as a usage range bound
+fn parse_range_date(value: &str, label: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").or_else(|_| {
         // Try parsing ISO datetime format
-        DateTime::parse_from_rfc3339(&end_date)
+        DateTime::parse_from_rfc3339(value)
             .map(|dt| dt.naive_local().date())
-            .map_err(|e| format!("Invalid end date: {}", e))
-    })?;
+            .map_err(|e| format!("Invalid {} date: {}", label, e))
+    })
+}
 
-    // Filter entries by date range
-    let filtered_entries: Vec<_> = all_entries
+/// Keeps entries whose timestamp falls within `start..=end` (inclusive days)
+fn filter_entries_by_date_range(
+    entries: Vec<UsageEntry>,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Vec<UsageEntry> {
+    entries
         .into_iter()
         .filter(|e| {
             if let Ok(dt) = DateTime::parse_from_rfc3339(&e.timestamp) {
@@ -481,7 +491,71 @@ pub fn get_usage_by_date_range(start_date: String, end_date: String) -> Result<U
                 false
             }
         })
-        .collect();
+        .collect()
+}
+
+/// Adds a single entry to the per-model totals
+fn accumulate_model_usage(model_stats: &mut HashMap<String, ModelUsage>, entry: &UsageEntry) {
+    let model_stat = model_stats
+        .entry(entry.model.clone())
+        .or_insert(ModelUsage {
+            model: entry.model.clone(),
+            total_cost: 0.0,
+            total_tokens: 0,
+            input_tokens: 0,
+            output_tokens: 0,
+            cache_creation_tokens: 0,
+            cache_read_tokens: 0,
+            session_count: 0,
+        });
+    model_stat.total_cost += entry.cost;
+    model_stat.input_tokens += entry.input_tokens;
+    model_stat.output_tokens += entry.output_tokens;
+    model_stat.cache_creation_tokens += entry.cache_creation_tokens;
+    model_stat.cache_read_tokens += entry.cache_read_tokens;
+    model_stat.total_tokens = model_stat.input_tokens + model_stat.output_tokens;
+    model_stat.session_count += 1;
+}
+
+/// Returns the first day of the bucket containing `date`
+fn bucket_start_for(date: NaiveDate, bucket: UsageBucket) -> NaiveDate {
+    match bucket {
+        UsageBucket::Day => date,
+        UsageBucket::Week => date - Duration::days(date.weekday().num_days_from_monday() as i64),
+        UsageBucket::Month => date.with_day(1).unwrap_or(date),
+    }
+}
+
+/// Returns the first day of the bucket following the one starting at `start`
+fn next_bucket_start(start: NaiveDate, bucket: UsageBucket) -> NaiveDate {
+    match bucket {
+        UsageBucket::Day => start + Duration::days(1),
+        UsageBucket::Week => start + Duration::days(7),
+        UsageBucket::Month => {
+            let (year, month) = if start.month() == 12 {
+                (start.year() + 1, 1)
+            } else {
+                (start.year(), start.month() + 1)
+            };
+            NaiveDate::from_ymd_opt(year, month, 1).unwrap_or(start + Duration::days(31))
+        }
+    }
+}
+
+#[command]
+pub fn get_usage_by_date_range(start_date: String, end_date: String) -> Result<UsageStats, String> {
+    let claude_path = dirs::home_dir()
+        .ok_or("Failed to get home directory")?
+        .join(".claude");
+
+    let all_entries = get_all_usage_entries(&claude_path);
+
+    // Parse dates
+    let start = parse_range_date(&start_date, "start")?;
+    let end = parse_range_date(&end_date, "end")?;
+
+    // Filter entries by date range
+    let filtered_entries = filter_entries_by_date_range(all_entries, start, end);
 
     if filtered_entries.is_empty() {
         return Ok(UsageStats {
@@ -518,25 +592,7 @@ pub fn get_usage_by_date_range(start_date: String, end_date: String) -> Result<U
         total_cache_read_tokens += entry.cache_read_tokens;
 
         // Update model stats
-        let model_stat = model_stats
-            .entry(entry.model.clone())
-            .or_insert(ModelUsage {
-                model: entry.model.clone(),
-                total_cost: 0.0,
-                total_tokens: 0,
-                input_tokens: 0,
-                output_tokens: 0,
-                cache_creation_tokens: 0,
-                cache_read_tokens: 0,
-                session_count: 0,
-            });
-        model_stat.total_cost += entry.cost;
-        model_stat.input_tokens += entry.input_tokens;
-        model_stat.output_tokens += entry.output_tokens;
-        model_stat.cache_creation_tokens += entry.cache_creation_tokens;
-        model_stat.cache_read_tokens += entry.cache_read_tokens;
-        model_stat.total_tokens = model_stat.input_tokens + model_stat.output_tokens;
-        model_stat.session_count += 1;
+        accumulate_model_usage(&mut model_stats, entry);
 
         // Update daily stats
         let date = entry
@@ -618,6 +674,82 @@ pub fn get_usage_by_date_range(start_date: String, end_date: String) -> Result<U
     })
 }
 
+/// Returns usage between `start` and `end` grouped into day, week or month
+/// buckets with per-model totals. Empty buckets are included so the series
+/// is continuous for charting.
+#[command]
+pub fn get_usage_timeseries(
+    start: String,
+    end: String,
+    bucket: UsageBucket,
+) -> Result<Vec<UsageTimeseriesBucket>, String> {
+    let start = parse_range_date(&start, "start")?;
+    let end = parse_range_date(&end, "end")?;
+    if start > end {
+        return Err(format!(
+            "Start date {} is after end date {}",
+            start.format("%Y-%m-%d"),
+            end.format("%Y-%m-%d")
+        ));
+    }
+
+    // Build the bucket skeleton first so oversized ranges fail before scanning logs
+    let mut bucket_starts = Vec::new();
+    let mut cursor = bucket_start_for(start, bucket);
+    while cursor <= end {
+        if bucket_starts.len() >= MAX_TIMESERIES_BUCKETS {
+            return Err(format!(
+                "Date range is too large: more than {} buckets",
+                MAX_TIMESERIES_BUCKETS
+            ));
+        }
+        bucket_starts.push(cursor);
+        cursor = next_bucket_start(cursor, bucket);
+    }
+
+    let claude_path = dirs::home_dir()
+        .ok_or("Failed to get home directory")?
+        .join(".claude");
+    let entries = filter_entries_by_date_range(get_all_usage_entries(&claude_path), start, end);
+
+    let mut bucket_stats: HashMap<NaiveDate, HashMap<String, ModelUsage>> = HashMap::new();
+    for entry in &entries {
+        let Ok(dt) = DateTime::parse_from_rfc3339(&entry.timestamp) else {
+            continue;
+        };
+        let key = bucket_start_for(dt.naive_local().date(), bucket);
+        accumulate_model_usage(bucket_stats.entry(key).or_default(), entry);
+    }
+
+    Ok(bucket_starts
+        .into_iter()
+        .map(|bucket_start| {
+            let bucket_end = (next_bucket_start(bucket_start, bucket) - Duration::days(1)).min(end);
+            let mut by_model: Vec<ModelUsage> = bucket_stats
+                .remove(&bucket_start)
+                .map(|stats| stats.into_values().collect())
+                .unwrap_or_default();
+            by_model.sort_by(|a, b| b.total_cost.partial_cmp(&a.total_cost).unwrap());
+
+            let total_cost = by_model.iter().map(|m| m.total_cost).sum();
+            let total_tokens = by_model
+                .iter()
+                .map(|m| {
+                    m.input_tokens + m.output_tokens + m.cache_creation_tokens + m.cache_read_tokens
+                })
+                .sum();
+
+            UsageTimeseriesBucket {
+                bucket_start: bucket_start.max(start).format("%Y-%m-%d").to_string(),
+                bucket_end: bucket_end.format("%Y-%m-%d").to_string(),
+                total_cost,
+                total_tokens,
+                by_model,
+            }
+        })
+        .collect())
+}
+
 #[command]
 pub fn get_usage_details(
     project_path: Option<String>,
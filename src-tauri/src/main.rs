@@ -8,45 +8,61 @@ mod process;
 
 use checkpoint::state::CheckpointState;
 use commands::agents::{
-    cleanup_finished_processes, create_agent, delete_agent, execute_agent, export_agent,
-    export_agent_to_file, fetch_github_agent_content, fetch_github_agents, get_agent,
-    get_agent_run, get_agent_run_with_real_time_metrics, get_claude_binary_path,
-    get_live_session_output, get_session_output, get_session_status, import_agent,
+    acknowledge_claude_binary_version, cancel_queued_run, check_claude_binary_drift,
+    check_sandbox_support, cleanup_finished_processes, create_agent, delete_agent,
+    delete_agent_runs, delete_runs_older_than, delete_setting, detect_claude_path_conflict,
+    estimate_agent_cost, execute_agent, export_agent, export_agent_runs, export_agent_to_file,
+    fetch_github_agent_content, fetch_github_agents, get_agent, get_agent_run,
+    get_agent_run_stderr, get_agent_run_with_real_time_metrics, get_claude_binary_path,
+    get_claude_fingerprint, get_claude_version_parts, get_effective_claude_env,
+    get_live_session_output, get_session_output, get_session_status, get_setting, import_agent,
     import_agent_from_file, import_agent_from_github, init_database, kill_agent_session,
     list_agent_runs, list_agent_runs_with_metrics, list_agents, list_claude_installations,
-    list_running_sessions, load_agent_session_history, set_claude_binary_path,
-    stream_session_output, update_agent, AgentDb,
+    list_process_ports, list_queued_agent_runs, list_running_sessions, load_agent_session_history,
+    refresh_claude_installations, reset_opcode_state, resume_agent_run_session,
+    run_ephemeral_agent, run_sandbox_self_test, run_self_check, set_claude_binary_path,
+    set_setting, stream_session_output, subscribe_agent_run, update_agent, AgentDb, AgentRunQueue,
 };
 use commands::claude::{
     cancel_claude_execution, check_auto_checkpoint, check_claude_version, cleanup_old_checkpoints,
     clear_checkpoint_manager, continue_claude_code, create_checkpoint, create_project,
-    execute_claude_code, find_claude_md_files, fork_from_checkpoint, get_checkpoint_diff,
-    get_checkpoint_settings, get_checkpoint_state_stats, get_claude_session_output,
-    get_claude_settings, get_home_directory, get_hooks_config, get_project_sessions,
-    get_recently_modified_files, get_session_timeline, get_system_prompt, list_checkpoints,
+    execute_claude_code, export_checkpoint, find_claude_md_files, find_project_root,
+    fork_from_checkpoint, get_anthropic_models, get_checkpoint_diff, get_checkpoint_settings,
+    get_checkpoint_state_stats, get_claude_session_log_dir, get_claude_session_output,
+    get_claude_settings, get_current_project, get_home_directory, get_hooks_config,
+    get_project_sessions, get_recently_modified_files, get_session_timeline, get_system_prompt,
+    import_checkpoint, list_checkpoints, list_checkpoints_with_stats, list_claude_sessions,
     list_directory_contents, list_projects, list_running_claude_sessions, load_session_history,
-    open_new_session, read_claude_md_file, restore_checkpoint, resume_claude_code,
-    save_claude_md_file, save_claude_settings, save_system_prompt, search_files,
+    on_project_changed, open_claude_session_logs, open_new_session, read_claude_md_file,
+    restore_checkpoint, resume_claude_code, save_claude_md_file, save_claude_settings,
+    save_system_prompt, search_files, set_current_project, test_claude_execution,
     track_checkpoint_message, track_session_messages, update_checkpoint_settings,
-    update_hooks_config, validate_hook_command, ClaudeProcessState,
+    update_hooks_config, validate_hook_command, validate_model_for_claude, ClaudeProcessState,
 };
 use commands::mcp::{
-    mcp_add, mcp_add_from_claude_desktop, mcp_add_json, mcp_get, mcp_get_server_status, mcp_list,
-    mcp_read_project_config, mcp_remove, mcp_reset_project_choices, mcp_save_project_config,
-    mcp_serve, mcp_test_connection,
+    cleanup_orphaned_mcp_processes, list_orphaned_mcp_processes, mcp_add,
+    mcp_add_from_claude_desktop, mcp_add_from_url, mcp_add_json, mcp_effective_servers, mcp_get,
+    mcp_get_server_status, mcp_health_check_all, mcp_list, mcp_list_raw, mcp_preview_add,
+    mcp_read_project_config, mcp_remove, mcp_reset_project_choices, mcp_restore_config,
+    mcp_save_project_config, mcp_serve, mcp_serve_status, mcp_serve_stop, mcp_snapshot_config,
+    mcp_test_connection, mcp_unwatch_config, mcp_validate_project_config, mcp_watch_config,
+    MCPConfigWatcher, MCPHealthCache,
 };
 
 use commands::proxy::{apply_proxy_settings, get_proxy_settings, save_proxy_settings};
+use commands::settings_profile::{export_settings_profile, import_settings_profile};
 use commands::storage::{
+    backup_agents_db, get_data_directory, get_storage_usage, restore_agents_db, set_data_directory,
     storage_delete_row, storage_execute_sql, storage_insert_row, storage_list_tables,
     storage_read_table, storage_reset_database, storage_update_row,
 };
 use commands::usage::{
     get_session_stats, get_usage_by_date_range, get_usage_details, get_usage_stats,
+    get_usage_timeseries,
 };
 use process::ProcessRegistryState;
 use std::sync::Mutex;
-use tauri::Manager;
+use tauri::{Emitter, Manager};
 
 #[cfg(target_os = "macos")]
 use window_vibrancy::{apply_vibrancy, NSVisualEffectMaterial};
@@ -118,8 +134,46 @@ fn main() {
 
             // Re-open the connection for the app to manage
             let conn = init_database(&app.handle()).expect("Failed to initialize agents database");
+            commands::slash_commands::load_commands_dir_override(&conn);
+            commands::claude::load_current_project(&conn);
+            if let Err(e) = commands::agents::cancel_stale_queued_runs(&conn) {
+                log::warn!("Failed to cancel stale queued agent runs: {}", e);
+            }
+            let sandbox_self_test = commands::agents::read_setting(
+                &conn,
+                commands::agents::SANDBOX_SELF_TEST_ON_STARTUP_KEY,
+            )
+            .ok()
+            .flatten()
+            .is_some_and(|value| value == "true");
             app.manage(AgentDb(Mutex::new(conn)));
 
+            // Opt-in check that the sandbox really enforces its rules
+            if sandbox_self_test {
+                let handle = app.handle().clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Ok(result) = commands::agents::run_sandbox_self_test().await {
+                        log::info!("Sandbox self-test: {:?}", result);
+                        let _ = handle.emit("sandbox-self-test", result);
+                    }
+                });
+            }
+
+            // Remove temp files orphaned by interrupted slash command writes
+            tauri::async_runtime::spawn(async {
+                let _ = commands::slash_commands::clean_command_temp_files(None).await;
+            });
+
+            // Warn when the shell's `claude` is not the one opcode will run
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                if let Ok(Some(conflict)) =
+                    commands::agents::detect_claude_path_conflict(handle.clone()).await
+                {
+                    let _ = handle.emit("claude-path-conflict", conflict);
+                }
+            });
+
             // Initialize checkpoint state
             let checkpoint_state = CheckpointState::new();
 
@@ -144,9 +198,18 @@ fn main() {
             // Initialize process registry
             app.manage(ProcessRegistryState::default());
 
+            // Agent runs waiting for a concurrency slot
+            app.manage(AgentRunQueue::default());
+
             // Initialize Claude process state
             app.manage(ClaudeProcessState::default());
 
+            // Initialize MCP health-check cache
+            app.manage(MCPHealthCache::default());
+
+            // MCP config watcher, started on demand by mcp_watch_config
+            app.manage(MCPConfigWatcher::default());
+
             // Apply window vibrancy with rounded corners on macOS
             #[cfg(target_os = "macos")]
             {
@@ -187,12 +250,22 @@ fn main() {
             // Claude & Project Management
             list_projects,
             create_project,
+            on_project_changed,
+            get_current_project,
+            set_current_project,
             get_project_sessions,
+            list_claude_sessions,
+            get_claude_session_log_dir,
+            open_claude_session_logs,
             get_home_directory,
+            find_project_root,
             get_claude_settings,
             open_new_session,
             get_system_prompt,
             check_claude_version,
+            get_anthropic_models,
+            test_claude_execution,
+            validate_model_for_claude,
             save_system_prompt,
             save_claude_settings,
             find_claude_md_files,
@@ -215,10 +288,13 @@ fn main() {
             create_checkpoint,
             restore_checkpoint,
             list_checkpoints,
+            list_checkpoints_with_stats,
             fork_from_checkpoint,
             get_session_timeline,
             update_checkpoint_settings,
             get_checkpoint_diff,
+            export_checkpoint,
+            import_checkpoint,
             track_checkpoint_message,
             track_session_messages,
             check_auto_checkpoint,
@@ -231,25 +307,50 @@ fn main() {
             create_agent,
             update_agent,
             delete_agent,
+            delete_agent_runs,
+            delete_runs_older_than,
             get_agent,
             execute_agent,
+            run_ephemeral_agent,
+            estimate_agent_cost,
             list_agent_runs,
             get_agent_run,
             list_agent_runs_with_metrics,
             get_agent_run_with_real_time_metrics,
+            resume_agent_run_session,
             list_running_sessions,
+            list_process_ports,
             kill_agent_session,
             get_session_status,
             cleanup_finished_processes,
             get_session_output,
+            get_agent_run_stderr,
             get_live_session_output,
             stream_session_output,
+            subscribe_agent_run,
             load_agent_session_history,
             get_claude_binary_path,
             set_claude_binary_path,
             list_claude_installations,
+            refresh_claude_installations,
+            detect_claude_path_conflict,
+            get_effective_claude_env,
+            get_claude_version_parts,
+            get_claude_fingerprint,
+            check_claude_binary_drift,
+            acknowledge_claude_binary_version,
+            run_self_check,
+            check_sandbox_support,
+            run_sandbox_self_test,
+            list_queued_agent_runs,
+            cancel_queued_run,
+            get_setting,
+            set_setting,
+            delete_setting,
+            reset_opcode_state,
             export_agent,
             export_agent_to_file,
+            export_agent_runs,
             import_agent,
             import_agent_from_file,
             fetch_github_agents,
@@ -259,20 +360,35 @@ fn main() {
             get_usage_stats,
             get_usage_by_date_range,
             get_usage_details,
+            get_usage_timeseries,
             get_session_stats,
             // MCP (Model Context Protocol)
             mcp_add,
+            mcp_preview_add,
             mcp_list,
+            mcp_list_raw,
             mcp_get,
             mcp_remove,
             mcp_add_json,
             mcp_add_from_claude_desktop,
+            mcp_add_from_url,
+            mcp_snapshot_config,
+            mcp_restore_config,
             mcp_serve,
+            mcp_serve_status,
+            mcp_serve_stop,
+            list_orphaned_mcp_processes,
+            cleanup_orphaned_mcp_processes,
             mcp_test_connection,
+            mcp_health_check_all,
             mcp_reset_project_choices,
             mcp_get_server_status,
+            mcp_watch_config,
+            mcp_unwatch_config,
             mcp_read_project_config,
+            mcp_effective_servers,
             mcp_save_project_config,
+            mcp_validate_project_config,
             // Storage Management
             storage_list_tables,
             storage_read_table,
@@ -281,15 +397,45 @@ fn main() {
             storage_insert_row,
             storage_execute_sql,
             storage_reset_database,
+            backup_agents_db,
+            restore_agents_db,
+            get_storage_usage,
+            get_data_directory,
+            set_data_directory,
             // Slash Commands
             commands::slash_commands::slash_commands_list,
+            commands::slash_commands::list_commands_since,
+            commands::slash_commands::list_command_executions,
             commands::slash_commands::slash_command_get,
             commands::slash_commands::slash_command_save,
             commands::slash_commands::slash_command_delete,
+            commands::slash_commands::find_duplicate_commands,
+            commands::slash_commands::merge_commands,
+            commands::slash_commands::open_command_in_editor,
+            commands::slash_commands::clean_command_temp_files,
+            commands::slash_commands::check_commands_dir_writable,
+            commands::slash_commands::import_commands_from_directory,
+            commands::slash_commands::export_commands_to_file,
+            commands::slash_commands::import_commands_from_file,
+            commands::slash_commands::cancel_command_import,
+            commands::slash_commands::search_slash_commands,
+            commands::slash_commands::list_command_tags,
+            commands::slash_commands::search_slash_commands_streaming,
+            commands::slash_commands::diff_command,
+            commands::slash_commands::get_commands_dir_override,
+            commands::slash_commands::set_commands_dir_override,
             // Proxy Settings
             get_proxy_settings,
             save_proxy_settings,
+            // Settings profiles
+            export_settings_profile,
+            import_settings_profile,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            if let tauri::RunEvent::Exit = event {
+                app.state::<MCPConfigWatcher>().stop();
+            }
+        });
 }
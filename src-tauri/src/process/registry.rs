@@ -1,14 +1,22 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex};
 use tokio::process::Child;
+use tokio::sync::broadcast;
 
 /// Type of process being tracked
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ProcessType {
-    AgentRun { agent_id: i64, agent_name: String },
-    ClaudeSession { session_id: String },
+    /// `agent_id` is `None` for ephemeral runs that have no stored agent
+    AgentRun {
+        agent_id: Option<i64>,
+        agent_name: String,
+    },
+    ClaudeSession {
+        session_id: String,
+    },
+    McpServe,
 }
 
 /// Information about a running agent process
@@ -23,6 +31,73 @@ pub struct ProcessInfo {
     pub model: String,
 }
 
+/// Lifecycle event published when a tracked process starts, exits or is killed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ProcessLifecycleEvent {
+    Started {
+        run_id: i64,
+        timestamp: DateTime<Utc>,
+    },
+    Exited {
+        run_id: i64,
+        code: Option<i32>,
+        timestamp: DateTime<Utc>,
+    },
+    Killed {
+        run_id: i64,
+        timestamp: DateTime<Utc>,
+    },
+    /// Events coalesced while a batch was open, in the order they happened
+    Batch {
+        events: Vec<ProcessLifecycleEvent>,
+        timestamp: DateTime<Utc>,
+    },
+}
+
+impl ProcessLifecycleEvent {
+    /// The run this event is about; `None` for a `Batch`
+    fn run_id(&self) -> Option<i64> {
+        match self {
+            Self::Started { run_id, .. }
+            | Self::Exited { run_id, .. }
+            | Self::Killed { run_id, .. } => Some(*run_id),
+            Self::Batch { .. } => None,
+        }
+    }
+}
+
+/// Lifecycle events held back while one or more batches are open
+#[derive(Default)]
+struct BatchState {
+    next_id: u64,
+    /// Open batches by id, each covering the given runs or, with `None`, every run
+    open: Vec<(u64, Option<HashSet<i64>>)>,
+    pending: Vec<ProcessLifecycleEvent>,
+}
+
+impl BatchState {
+    /// Whether an open batch holds back `event`
+    fn covers(&self, event: &ProcessLifecycleEvent) -> bool {
+        self.open.iter().any(|(_, runs)| {
+            runs.as_ref()
+                .is_none_or(|runs| event.run_id().is_some_and(|id| runs.contains(&id)))
+        })
+    }
+}
+
+/// Open lifecycle batch; the batch ends when this is dropped, even on early return
+pub struct LifecycleBatch<'a> {
+    registry: &'a ProcessRegistry,
+    id: u64,
+}
+
+impl Drop for LifecycleBatch<'_> {
+    fn drop(&mut self) {
+        self.registry.end_batch(self.id);
+    }
+}
+
 /// Information about a running process with handle
 #[allow(dead_code)]
 pub struct ProcessHandle {
@@ -35,14 +110,110 @@ pub struct ProcessHandle {
 pub struct ProcessRegistry {
     processes: Arc<Mutex<HashMap<i64, ProcessHandle>>>, // run_id -> ProcessHandle
     next_id: Arc<Mutex<i64>>, // Auto-incrementing ID for non-agent processes
+    lifecycle_events: broadcast::Sender<ProcessLifecycleEvent>,
+    batch: Mutex<BatchState>,
 }
 
 impl ProcessRegistry {
     pub fn new() -> Self {
+        let (lifecycle_events, _) = broadcast::channel(100);
         Self {
             processes: Arc::new(Mutex::new(HashMap::new())),
             next_id: Arc::new(Mutex::new(1000000)), // Start at high number to avoid conflicts
+            lifecycle_events,
+            batch: Mutex::new(BatchState::default()),
+        }
+    }
+
+    /// Subscribe to process lifecycle events
+    pub fn subscribe_lifecycle(&self) -> broadcast::Receiver<ProcessLifecycleEvent> {
+        self.lifecycle_events.subscribe()
+    }
+
+    /// Publish a lifecycle event to all subscribers, or hold it while a batch is open
+    fn publish_lifecycle(&self, event: ProcessLifecycleEvent) {
+        let mut batch = self.batch.lock().unwrap_or_else(|e| e.into_inner());
+        if batch.covers(&event) {
+            batch.pending.push(event);
+            return;
         }
+        drop(batch);
+
+        // Sending only fails when nobody is subscribed, which is fine
+        let _ = self.lifecycle_events.send(event);
+    }
+
+    /// Start coalescing lifecycle events for `runs`, or every run with `None`
+    ///
+    /// Batches can overlap. Registry changes still apply immediately, only their
+    /// events are deferred. Returns the id to pass to `end_batch`; prefer `batch_guard`
+    /// or `batch_guard_for`.
+    fn begin_batch(&self, runs: Option<HashSet<i64>>) -> u64 {
+        let mut batch = self.batch.lock().unwrap_or_else(|e| e.into_inner());
+        batch.next_id += 1;
+        let id = batch.next_id;
+        batch.open.push((id, runs));
+        id
+    }
+
+    /// Close a batch and publish the pending events no other open batch covers
+    ///
+    /// A single released event is published as-is, several as one `Batch` event.
+    fn end_batch(&self, id: u64) {
+        let mut events = {
+            let mut batch = self.batch.lock().unwrap_or_else(|e| e.into_inner());
+            batch.open.retain(|(open_id, _)| *open_id != id);
+            let (held, released): (Vec<_>, Vec<_>) = std::mem::take(&mut batch.pending)
+                .into_iter()
+                .partition(|event| batch.covers(event));
+            batch.pending = held;
+            released
+        };
+
+        let event = match events.len() {
+            0 => return,
+            1 => events.remove(0),
+            _ => ProcessLifecycleEvent::Batch {
+                events,
+                timestamp: Utc::now(),
+            },
+        };
+        let _ = self.lifecycle_events.send(event);
+    }
+
+    /// Open a batch over every run that ends when the returned guard is dropped
+    ///
+    /// Don't hold this across `.await` points, since it holds back every other
+    /// run's events too; use `batch_guard_for` there.
+    pub fn batch_guard(&self) -> LifecycleBatch<'_> {
+        let id = self.begin_batch(None);
+        LifecycleBatch { registry: self, id }
+    }
+
+    /// Open a batch over just `runs`; events for other runs publish as usual
+    ///
+    /// Safe to hold across `.await` points, e.g. when killing several processes.
+    pub fn batch_guard_for(&self, runs: impl IntoIterator<Item = i64>) -> LifecycleBatch<'_> {
+        let id = self.begin_batch(Some(runs.into_iter().collect()));
+        LifecycleBatch { registry: self, id }
+    }
+
+    /// Record that a process has exited, publishing its exit code if known
+    pub fn notify_exited(&self, run_id: i64, code: Option<i32>) {
+        self.publish_lifecycle(ProcessLifecycleEvent::Exited {
+            run_id,
+            code,
+            timestamp: Utc::now(),
+        });
+    }
+
+    /// Get the exit code of a process whose child handle is still held, if it has exited
+    pub fn try_exit_code(&self, run_id: i64) -> Option<i32> {
+        let processes = self.processes.lock().ok()?;
+        let handle = processes.get(&run_id)?;
+        let mut child_guard = handle.child.lock().ok()?;
+        let status = child_guard.as_mut()?.try_wait().ok()??;
+        status.code()
     }
 
     /// Generate a unique ID for non-agent processes
@@ -57,7 +228,7 @@ impl ProcessRegistry {
     pub fn register_process(
         &self,
         run_id: i64,
-        agent_id: i64,
+        agent_id: Option<i64>,
         agent_name: String,
         pid: u32,
         project_path: String,
@@ -85,7 +256,7 @@ impl ProcessRegistry {
     pub fn register_sidecar_process(
         &self,
         run_id: i64,
-        agent_id: i64,
+        agent_id: Option<i64>,
         agent_name: String,
         pid: u32,
         project_path: String,
@@ -115,6 +286,12 @@ impl ProcessRegistry {
         };
 
         processes.insert(run_id, process_handle);
+        drop(processes);
+
+        self.publish_lifecycle(ProcessLifecycleEvent::Started {
+            run_id,
+            timestamp: Utc::now(),
+        });
         Ok(())
     }
 
@@ -149,9 +326,61 @@ impl ProcessRegistry {
         };
 
         processes.insert(run_id, process_handle);
+        drop(processes);
+
+        self.publish_lifecycle(ProcessLifecycleEvent::Started {
+            run_id,
+            timestamp: Utc::now(),
+        });
         Ok(run_id)
     }
 
+    /// Register a `claude mcp serve` process started by the app
+    pub fn register_mcp_serve(&self, pid: u32, child: Child) -> Result<i64, String> {
+        let run_id = self.generate_id()?;
+
+        let process_info = ProcessInfo {
+            run_id,
+            process_type: ProcessType::McpServe,
+            pid,
+            started_at: Utc::now(),
+            project_path: String::new(),
+            task: "mcp serve".to_string(),
+            model: String::new(),
+        };
+
+        self.register_process_internal(run_id, process_info, child)?;
+        Ok(run_id)
+    }
+
+    /// Get all running `mcp serve` processes, most recently started first
+    ///
+    /// Processes that have already exited are unregistered along the way.
+    pub fn get_mcp_serve_processes(&self) -> Result<Vec<ProcessInfo>, String> {
+        let candidates: Vec<ProcessInfo> = {
+            let processes = self.processes.lock().map_err(|e| e.to_string())?;
+            processes
+                .values()
+                .filter(|handle| handle.info.task.contains("mcp serve"))
+                .map(|handle| handle.info.clone())
+                .collect()
+        };
+
+        let mut running = Vec::new();
+        let _batch = self.batch_guard();
+        for info in candidates {
+            if let Some(code) = self.try_exit_code(info.run_id) {
+                self.unregister_process(info.run_id)?;
+                self.notify_exited(info.run_id, Some(code));
+            } else {
+                running.push(info);
+            }
+        }
+
+        running.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+        Ok(running)
+    }
+
     /// Internal method to register any process
     fn register_process_internal(
         &self,
@@ -168,6 +397,12 @@ impl ProcessRegistry {
         };
 
         processes.insert(run_id, process_handle);
+        drop(processes);
+
+        self.publish_lifecycle(ProcessLifecycleEvent::Started {
+            run_id,
+            timestamp: Utc::now(),
+        });
         Ok(())
     }
 
@@ -285,7 +520,7 @@ impl ProcessRegistry {
                 "Attempting fallback kill for process {} (PID: {})",
                 run_id, pid
             );
-            match self.kill_process_by_pid(run_id, pid) {
+            match self.kill_process_by_pid_async(run_id, pid).await {
                 Ok(true) => return Ok(true),
                 Ok(false) => warn!(
                     "Fallback kill also failed for process {} (PID: {})",
@@ -348,22 +583,54 @@ impl ProcessRegistry {
                 if let Ok(mut child_guard) = child_arc.lock() {
                     *child_guard = None;
                 }
-                // One more attempt with system kill
-                let _ = self.kill_process_by_pid(run_id, pid);
+                // One more attempt with system kill, which unregisters and announces on success
+                if let Ok(true) = self.kill_process_by_pid_async(run_id, pid).await {
+                    return Ok(true);
+                }
             }
         }
 
         // Remove from registry after killing
-        self.unregister_process(run_id)?;
-
+        self.forget_killed(run_id)?;
         Ok(true)
     }
 
     /// Kill a process by PID using system commands (fallback method)
     pub fn kill_process_by_pid(&self, run_id: i64, pid: u32) -> Result<bool, String> {
-        use log::{error, info, warn};
+        log::info!("Attempting to kill process {} by PID {}", run_id, pid);
+        let killed = Self::system_kill(pid)?;
+        if killed {
+            self.forget_killed(run_id)?;
+        }
+        Ok(killed)
+    }
 
-        info!("Attempting to kill process {} by PID {}", run_id, pid);
+    /// `kill_process_by_pid` for async callers; the system kill shells out and
+    /// sleeps, so it runs on the blocking pool
+    async fn kill_process_by_pid_async(&self, run_id: i64, pid: u32) -> Result<bool, String> {
+        log::info!("Attempting to kill process {} by PID {}", run_id, pid);
+        let killed = tokio::task::spawn_blocking(move || Self::system_kill(pid))
+            .await
+            .map_err(|e| format!("Kill task failed: {}", e))??;
+        if killed {
+            self.forget_killed(run_id)?;
+        }
+        Ok(killed)
+    }
+
+    /// Remove a killed process from the registry and announce it
+    fn forget_killed(&self, run_id: i64) -> Result<(), String> {
+        self.unregister_process(run_id)?;
+        self.publish_lifecycle(ProcessLifecycleEvent::Killed {
+            run_id,
+            timestamp: Utc::now(),
+        });
+        Ok(())
+    }
+
+    /// Terminate `pid` with `taskkill` or `kill`, escalating from SIGTERM to SIGKILL
+    fn system_kill(pid: u32) -> Result<bool, String> {
+        use log::{error, info, warn};
 
         let kill_result = if cfg!(target_os = "windows") {
             std::process::Command::new("taskkill")
@@ -417,8 +684,6 @@ impl ProcessRegistry {
             Ok(output) => {
                 if output.status.success() {
                     info!("Successfully killed process with PID {}", pid);
-                    // Remove from registry
-                    self.unregister_process(run_id)?;
                     Ok(true)
                 } else {
                     let error_msg = String::from_utf8_lossy(&output.stderr);
@@ -535,3 +800,134 @@ impl Default for ProcessRegistryState {
         Self(Arc::new(ProcessRegistry::new()))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::broadcast::error::TryRecvError;
+
+    fn started(run_id: i64) -> ProcessLifecycleEvent {
+        ProcessLifecycleEvent::Started {
+            run_id,
+            timestamp: Utc::now(),
+        }
+    }
+
+    fn killed(run_id: i64) -> ProcessLifecycleEvent {
+        ProcessLifecycleEvent::Killed {
+            run_id,
+            timestamp: Utc::now(),
+        }
+    }
+
+    /// Run ids of a received event, flattening a `Batch`
+    fn run_ids(event: &ProcessLifecycleEvent) -> Vec<i64> {
+        match event {
+            ProcessLifecycleEvent::Batch { events, .. } => {
+                events.iter().filter_map(|e| e.run_id()).collect()
+            }
+            other => other.run_id().into_iter().collect(),
+        }
+    }
+
+    #[test]
+    fn test_batch_guard_coalesces_events() {
+        let registry = ProcessRegistry::new();
+        let mut rx = registry.subscribe_lifecycle();
+
+        registry.publish_lifecycle(started(1));
+        let before = rx.try_recv().unwrap();
+        assert!(matches!(
+            before,
+            ProcessLifecycleEvent::Started { run_id: 1, .. }
+        ));
+
+        {
+            let _batch = registry.batch_guard();
+            registry.publish_lifecycle(killed(1));
+            registry.publish_lifecycle(started(2));
+            registry.publish_lifecycle(killed(2));
+            assert!(matches!(rx.try_recv(), Err(TryRecvError::Empty)));
+        }
+
+        let batch = rx.try_recv().unwrap();
+        assert!(matches!(batch, ProcessLifecycleEvent::Batch { .. }));
+        assert_eq!(run_ids(&batch), vec![1, 2, 2]);
+
+        registry.publish_lifecycle(killed(3));
+        let after = rx.try_recv().unwrap();
+        assert!(matches!(
+            after,
+            ProcessLifecycleEvent::Killed { run_id: 3, .. }
+        ));
+        assert!(matches!(rx.try_recv(), Err(TryRecvError::Empty)));
+    }
+
+    #[test]
+    fn test_batch_with_one_event_publishes_it_unwrapped() {
+        let registry = ProcessRegistry::new();
+        let mut rx = registry.subscribe_lifecycle();
+
+        drop(registry.batch_guard());
+        assert!(matches!(rx.try_recv(), Err(TryRecvError::Empty)));
+
+        {
+            let _batch = registry.batch_guard();
+            registry.publish_lifecycle(killed(7));
+        }
+        let event = rx.try_recv().unwrap();
+        assert!(matches!(
+            event,
+            ProcessLifecycleEvent::Killed { run_id: 7, .. }
+        ));
+    }
+
+    #[test]
+    fn test_batch_guard_for_only_holds_its_runs() {
+        let registry = ProcessRegistry::new();
+        let mut rx = registry.subscribe_lifecycle();
+
+        {
+            let _batch = registry.batch_guard_for([1, 2]);
+            registry.publish_lifecycle(killed(1));
+            registry.publish_lifecycle(started(5));
+            let unrelated = rx.try_recv().unwrap();
+            assert!(matches!(
+                unrelated,
+                ProcessLifecycleEvent::Started { run_id: 5, .. }
+            ));
+            registry.publish_lifecycle(killed(2));
+        }
+
+        let batch = rx.try_recv().unwrap();
+        assert_eq!(run_ids(&batch), vec![1, 2]);
+        assert!(matches!(rx.try_recv(), Err(TryRecvError::Empty)));
+    }
+
+    #[test]
+    fn test_overlapping_batches_release_events_when_uncovered() {
+        let registry = ProcessRegistry::new();
+        let mut rx = registry.subscribe_lifecycle();
+
+        let outer = registry.batch_guard_for([1]);
+        {
+            let _inner = registry.batch_guard_for([1, 2]);
+            registry.publish_lifecycle(killed(1));
+            registry.publish_lifecycle(killed(2));
+        }
+        // Run 2 is released with the inner batch; run 1 waits for the outer one
+        let released = rx.try_recv().unwrap();
+        assert!(matches!(
+            released,
+            ProcessLifecycleEvent::Killed { run_id: 2, .. }
+        ));
+        assert!(matches!(rx.try_recv(), Err(TryRecvError::Empty)));
+
+        drop(outer);
+        let released = rx.try_recv().unwrap();
+        assert!(matches!(
+            released,
+            ProcessLifecycleEvent::Killed { run_id: 1, .. }
+        ));
+    }
+}
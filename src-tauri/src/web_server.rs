@@ -1,17 +1,19 @@
 use axum::extract::ws::{Message, WebSocket};
-use axum::http::Method;
+use axum::http::{header, Method, StatusCode};
 use axum::{
-    extract::{Path, State as AxumState, WebSocketUpgrade},
-    response::{Html, Json, Response},
-    routing::get,
+    extract::{ConnectInfo, Path, Query, Request, State as AxumState, WebSocketUpgrade},
+    middleware::{self, Next},
+    response::{Html, IntoResponse, Json, Response},
+    routing::{get, post},
     Router,
 };
 use chrono;
 use futures_util::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use std::net::SocketAddr;
+use std::net::{IpAddr, SocketAddr};
 use std::sync::Arc;
+use std::time::Instant;
 use tokio::net::TcpListener;
 use tokio::sync::Mutex;
 use tower_http::cors::{Any, CorsLayer};
@@ -64,6 +66,10 @@ pub struct AppState {
     // Track active WebSocket sessions for Claude execution
     pub active_sessions:
         Arc<Mutex<std::collections::HashMap<String, tokio::sync::mpsc::Sender<String>>>>,
+    // Registry of Claude processes spawned in web mode, used for lifecycle events
+    pub process_registry: Arc<crate::process::ProcessRegistry>,
+    // Limits Claude spawns, shared by the execution routes and WebSocket messages
+    pub execute_limiter: RateLimiter,
 }
 
 #[derive(Debug, Deserialize)]
@@ -75,72 +81,342 @@ pub struct ClaudeExecutionRequest {
     pub command_type: String, // "execute", "continue", or "resume"
 }
 
+#[derive(Debug, Deserialize)]
+pub struct SlashCommandExecutionRequest {
+    /// WebSocket session that receives the streamed output
+    pub session_id: String,
+    pub model: Option<String>,
+    pub args: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CommandExportRequest {
+    /// File name to create in the server's exports directory
+    pub file_name: String,
+    pub compress: Option<bool>,
+    /// Also export this project's commands
+    pub project_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CommandListParams {
+    #[serde(default)]
+    pub include_content: Option<bool>,
+}
+
 #[derive(Deserialize)]
 pub struct QueryParams {
     #[serde(default)]
     pub project_path: Option<String>,
 }
 
+/// Successful response envelope: `{ "data": ... }`
 #[derive(Serialize)]
 pub struct ApiResponse<T> {
-    pub success: bool,
-    pub data: Option<T>,
-    pub error: Option<String>,
+    pub data: T,
 }
 
 impl<T> ApiResponse<T> {
     pub fn success(data: T) -> Self {
+        Self { data }
+    }
+}
+
+/// Error returned by web handlers, serialized as `{ "error": { code, message, details? } }`
+#[derive(Debug, Serialize)]
+pub struct ApiError {
+    #[serde(skip)]
+    pub status: StatusCode,
+    /// Stable machine-readable error code, e.g. `not_found`
+    pub code: String,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<serde_json::Value>,
+}
+
+impl ApiError {
+    pub fn new(status: StatusCode, code: impl Into<String>, message: impl Into<String>) -> Self {
         Self {
-            success: true,
-            data: Some(data),
-            error: None,
+            status,
+            code: code.into(),
+            message: message.into(),
+            details: None,
         }
     }
 
-    pub fn error(error: String) -> Self {
+    pub fn bad_request(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, "bad_request", message)
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::NOT_FOUND, "not_found", message)
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, "internal", message)
+    }
+
+    pub fn not_available(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::NOT_IMPLEMENTED, "not_available", message)
+    }
+
+    pub fn rate_limited(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::TOO_MANY_REQUESTS, "rate_limited", message)
+    }
+
+    pub fn unauthorized(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::UNAUTHORIZED, "unauthorized", message)
+    }
+
+    pub fn with_details(mut self, details: serde_json::Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+}
+
+impl From<commands::slash_commands::CommandError> for ApiError {
+    fn from(error: commands::slash_commands::CommandError) -> Self {
+        use commands::slash_commands::CommandError;
+        let status = match &error {
+            CommandError::NotFound(_) => StatusCode::NOT_FOUND,
+            CommandError::InvalidName(_) | CommandError::InvalidInput(_) => StatusCode::BAD_REQUEST,
+            CommandError::AlreadyExists(_) | CommandError::ContentMismatch(_) => {
+                StatusCode::CONFLICT
+            }
+            CommandError::Io(_) | CommandError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        Self::new(status, error.code(), error.message())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.status, Json(json!({ "error": self }))).into_response()
+    }
+}
+
+/// Result type shared by all REST handlers
+type ApiResult<T> = Result<Json<ApiResponse<T>>, ApiError>;
+
+/// Wrap handler data in the `{ "data": ... }` envelope
+fn success_response<T>(data: T) -> ApiResult<T> {
+    Ok(Json(ApiResponse::success(data)))
+}
+
+/// Message returned by execution endpoints that only work over the WebSocket
+const WEB_EXECUTION_UNAVAILABLE: &str = "Claude execution is not available in web mode. Please use the desktop app for running Claude commands.";
+
+/// Token-bucket limits applied to endpoints that spawn Claude processes
+#[derive(Debug, Clone)]
+pub struct RateLimitConfig {
+    /// Maximum number of requests that can be made in a burst
+    pub burst: u32,
+    /// Tokens restored per second once the burst is spent
+    pub refill_per_sec: f64,
+    /// Track a separate bucket per client IP instead of one shared bucket
+    pub per_ip: bool,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
         Self {
-            success: false,
-            data: None,
-            error: Some(error),
+            burst: 10,
+            refill_per_sec: 0.5,
+            per_ip: true,
         }
     }
 }
 
+/// Configuration for the web server
+#[derive(Debug, Clone)]
+pub struct WebServerConfig {
+    pub port: u16,
+    /// Limits for execution routes; read-only routes are not limited
+    pub execute_rate_limit: RateLimitConfig,
+    /// Bearer token for routes that run slash commands or write files;
+    /// without one those routes only accept loopback clients
+    pub auth_token: Option<String>,
+}
+
+impl Default for WebServerConfig {
+    fn default() -> Self {
+        Self {
+            port: 8080,
+            execute_rate_limit: RateLimitConfig::default(),
+            auth_token: None,
+        }
+    }
+}
+
+/// Environment variable `start_web_mode` reads the auth token from
+pub const WEB_AUTH_TOKEN_ENV: &str = "OPCODE_WEB_TOKEN";
+
+/// Compare tokens without exiting early on the first differing byte
+fn token_matches(expected: &str, provided: &str) -> bool {
+    expected.len() == provided.len()
+        && expected
+            .bytes()
+            .zip(provided.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Middleware guarding routes that run slash commands or write files
+///
+/// With a token configured, requests must send `Authorization: Bearer <token>`.
+/// Without one, only loopback clients are allowed, since the server binds every interface.
+async fn require_auth(
+    AxumState(token): AxumState<Option<String>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let allowed = match &token {
+        Some(token) => request
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|provided| token_matches(token, provided.trim())),
+        None => addr.ip().to_canonical().is_loopback(),
+    };
+    if allowed {
+        return next.run(request).await;
+    }
+
+    println!(
+        "[TRACE] Rejected unauthenticated request from {} to {}",
+        addr.ip(),
+        request.uri().path()
+    );
+    let message = match token {
+        Some(_) => "Missing or invalid bearer token".to_string(),
+        None => format!(
+            "Only local clients may use this endpoint; set {} to allow remote access",
+            WEB_AUTH_TOKEN_ENV
+        ),
+    };
+    ApiError::unauthorized(message).into_response()
+}
+
+/// Number of tracked client buckets above which idle (full) buckets are pruned
+const MAX_TRACKED_BUCKETS: usize = 1024;
+
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn refill(&mut self, config: &RateLimitConfig, now: Instant) {
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * config.refill_per_sec).min(config.burst as f64);
+        self.last_refill = now;
+    }
+}
+
+/// Token-bucket rate limiter keyed by client IP (or a single global bucket)
+#[derive(Clone)]
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    buckets: Arc<std::sync::Mutex<std::collections::HashMap<Option<IpAddr>, TokenBucket>>>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            config,
+            buckets: Arc::new(std::sync::Mutex::new(std::collections::HashMap::new())),
+        }
+    }
+
+    /// Takes a token for the given client, returning false when the bucket is empty
+    pub fn try_acquire(&self, ip: IpAddr) -> bool {
+        let key = if self.config.per_ip { Some(ip) } else { None };
+        let now = Instant::now();
+        let mut buckets = match self.buckets.lock() {
+            Ok(buckets) => buckets,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+
+        if buckets.len() > MAX_TRACKED_BUCKETS {
+            let config = &self.config;
+            buckets.retain(|_, bucket| {
+                bucket.refill(config, now);
+                bucket.tokens < config.burst as f64
+            });
+        }
+
+        let bucket = buckets.entry(key).or_insert_with(|| TokenBucket {
+            tokens: self.config.burst as f64,
+            last_refill: now,
+        });
+        bucket.refill(&self.config, now);
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Middleware rejecting execution requests with 429 once the client's bucket is empty
+async fn rate_limit_execute(
+    AxumState(limiter): AxumState<RateLimiter>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if limiter.try_acquire(addr.ip()) {
+        return next.run(request).await;
+    }
+
+    println!(
+        "[TRACE] Rate limit exceeded for {} on {}",
+        addr.ip(),
+        request.uri().path()
+    );
+    ApiError::rate_limited(EXECUTE_RATE_LIMITED).into_response()
+}
+
+/// Error reported when a client exceeds the execution rate limit
+const EXECUTE_RATE_LIMITED: &str = "Too many execution requests, please slow down";
+
 /// Serve the React frontend
 async fn serve_frontend() -> Html<&'static str> {
     Html(include_str!("../../dist/index.html"))
 }
 
 /// API endpoint to get projects (equivalent to Tauri command)
-async fn get_projects() -> Json<ApiResponse<Vec<commands::claude::Project>>> {
+async fn get_projects() -> ApiResult<Vec<commands::claude::Project>> {
     match commands::claude::list_projects().await {
-        Ok(projects) => Json(ApiResponse::success(projects)),
-        Err(e) => Json(ApiResponse::error(e.to_string())),
+        Ok(projects) => success_response(projects),
+        Err(e) => Err(ApiError::internal(e)),
     }
 }
 
 /// API endpoint to get sessions for a project
-async fn get_sessions(
-    Path(project_id): Path<String>,
-) -> Json<ApiResponse<Vec<commands::claude::Session>>> {
+async fn get_sessions(Path(project_id): Path<String>) -> ApiResult<Vec<commands::claude::Session>> {
     match commands::claude::get_project_sessions(project_id).await {
-        Ok(sessions) => Json(ApiResponse::success(sessions)),
-        Err(e) => Json(ApiResponse::error(e.to_string())),
+        Ok(sessions) => success_response(sessions),
+        Err(e) => Err(ApiError::internal(e)),
     }
 }
 
 /// Simple agents endpoint - return empty for now (needs DB state)
-async fn get_agents() -> Json<ApiResponse<Vec<serde_json::Value>>> {
-    Json(ApiResponse::success(vec![]))
+async fn get_agents() -> ApiResult<Vec<serde_json::Value>> {
+    success_response(vec![])
 }
 
 /// Simple usage endpoint - return empty for now
-async fn get_usage() -> Json<ApiResponse<Vec<serde_json::Value>>> {
-    Json(ApiResponse::success(vec![]))
+async fn get_usage() -> ApiResult<Vec<serde_json::Value>> {
+    success_response(vec![])
 }
 
 /// Get Claude settings - return basic defaults for web mode
-async fn get_claude_settings() -> Json<ApiResponse<serde_json::Value>> {
+async fn get_claude_settings() -> ApiResult<serde_json::Value> {
     let default_settings = serde_json::json!({
         "data": {
             "model": "claude-3-5-sonnet-20241022",
@@ -150,111 +426,286 @@ async fn get_claude_settings() -> Json<ApiResponse<serde_json::Value>> {
             "theme": "dark"
         }
     });
-    Json(ApiResponse::success(default_settings))
+    success_response(default_settings)
 }
 
 /// Check Claude version - return mock status for web mode
-async fn check_claude_version() -> Json<ApiResponse<serde_json::Value>> {
+async fn check_claude_version() -> ApiResult<serde_json::Value> {
     let version_status = serde_json::json!({
         "status": "ok",
         "version": "web-mode",
         "message": "Running in web server mode"
     });
-    Json(ApiResponse::success(version_status))
+    success_response(version_status)
 }
 
 /// List all available Claude installations on the system
-async fn list_claude_installations(
-) -> Json<ApiResponse<Vec<crate::claude_binary::ClaudeInstallation>>> {
-    let installations = crate::claude_binary::discover_claude_installations();
+async fn list_claude_installations() -> ApiResult<Vec<crate::claude_binary::ClaudeInstallation>> {
+    let installations = crate::claude_binary::discover_claude_installations(&[]);
 
     if installations.is_empty() {
-        Json(ApiResponse::error(
-            "No Claude Code installations found on the system".to_string(),
+        Err(ApiError::not_found(
+            "No Claude Code installations found on the system",
         ))
     } else {
-        Json(ApiResponse::success(installations))
+        success_response(installations)
     }
 }
 
 /// Get system prompt - return default for web mode
-async fn get_system_prompt() -> Json<ApiResponse<String>> {
+async fn get_system_prompt() -> ApiResult<String> {
     let default_prompt =
         "You are Claude, an AI assistant created by Anthropic. You are running in web server mode."
             .to_string();
-    Json(ApiResponse::success(default_prompt))
+    success_response(default_prompt)
 }
 
 /// Open new session - mock for web mode
-async fn open_new_session() -> Json<ApiResponse<String>> {
+async fn open_new_session() -> ApiResult<String> {
     let session_id = format!("web-session-{}", chrono::Utc::now().timestamp());
-    Json(ApiResponse::success(session_id))
+    success_response(session_id)
 }
 
 /// List slash commands - return empty for web mode
-async fn list_slash_commands() -> Json<ApiResponse<Vec<serde_json::Value>>> {
-    Json(ApiResponse::success(vec![]))
+async fn list_slash_commands() -> ApiResult<Vec<serde_json::Value>> {
+    success_response(vec![])
+}
+
+/// Resolve a project ID to its path, rejecting projects Claude doesn't know about
+async fn resolve_project_path(project_id: &str) -> Result<String, ApiError> {
+    let projects = commands::claude::list_projects()
+        .await
+        .map_err(ApiError::internal)?;
+    projects
+        .into_iter()
+        .find(|project| project.id == project_id)
+        .map(|project| project.path)
+        .filter(|path| std::path::Path::new(path).is_dir())
+        .ok_or_else(|| ApiError::not_found(format!("Unknown project: {}", project_id)))
+}
+
+/// List the slash commands available in a project (project and user scope)
+///
+/// `?include_content=false` omits command bodies from the listing.
+async fn list_project_slash_commands(
+    Path(project_id): Path<String>,
+    Query(params): Query<CommandListParams>,
+) -> ApiResult<Vec<commands::slash_commands::SlashCommand>> {
+    let project_path = resolve_project_path(&project_id).await?;
+    let commands = commands::slash_commands::slash_commands_list(
+        Some(project_path),
+        None,
+        None,
+        params.include_content,
+    )
+    .await?;
+    success_response(commands)
+}
+
+/// Add a slash command run to the execution history without blocking the runtime
+fn record_execution_in_background(
+    command: commands::slash_commands::SlashCommand,
+    prompt: String,
+    project_path: String,
+    model: String,
+) {
+    tokio::task::spawn_blocking(move || {
+        commands::slash_commands::record_command_execution(&command, &prompt, &project_path, &model)
+    });
+}
+
+/// Start a slash command in a project, streaming output to an open WebSocket session
+///
+/// Returns the session ID right away; output and completion arrive over the WebSocket.
+async fn execute_project_slash_command(
+    Path((project_id, name)): Path<(String, String)>,
+    AxumState(state): AxumState<AppState>,
+    Json(request): Json<SlashCommandExecutionRequest>,
+) -> ApiResult<String> {
+    let project_path = resolve_project_path(&project_id).await?;
+
+    if !state
+        .active_sessions
+        .lock()
+        .await
+        .contains_key(&request.session_id)
+    {
+        return Err(ApiError::bad_request(format!(
+            "No active WebSocket session: {}",
+            request.session_id
+        )));
+    }
+
+    let prompt = match request.args.as_deref().map(str::trim) {
+        Some(args) if !args.is_empty() => format!("/{} {}", name, args),
+        _ => format!("/{}", name),
+    };
+    let Some(command) =
+        commands::slash_commands::find_invoked_command(&prompt, Some(project_path.clone())).await
+    else {
+        return Err(ApiError::not_found(format!("Command not found: /{}", name)));
+    };
+
+    let model =
+        commands::slash_commands::resolve_command_model(Some(&command), request.model, "sonnet");
+    let tool_args = commands::slash_commands::command_tool_args(&command);
+    record_execution_in_background(command, prompt.clone(), project_path.clone(), model.clone());
+    let session_id = request.session_id;
+
+    let task_session_id = session_id.clone();
+    tokio::spawn(async move {
+        let result = execute_claude_command(
+            project_path,
+            prompt,
+            model,
+            tool_args,
+            task_session_id.clone(),
+            state.clone(),
+        )
+        .await;
+
+        let completion_msg = match result {
+            Ok(_) => json!({
+                "type": "completion",
+                "status": "success"
+            }),
+            Err(e) => json!({
+                "type": "completion",
+                "status": "error",
+                "error": e
+            }),
+        };
+        send_to_session(&state, &task_session_id, completion_msg.to_string()).await;
+    });
+
+    success_response(session_id)
+}
+
+/// Directory web-mode command exports are written to
+///
+/// Clients only choose a file name inside this directory under the app data dir,
+/// never an arbitrary path.
+fn web_exports_dir() -> Result<std::path::PathBuf, String> {
+    Ok(commands::storage::current_app_data_dir()?.join("exports"))
+}
+
+/// Check that an export name is a plain file name with no directory parts
+fn validate_export_file_name(file_name: &str) -> Result<&str, String> {
+    let file_name = file_name.trim();
+    let is_plain = !file_name.is_empty()
+        && !file_name.starts_with('.')
+        && std::path::Path::new(file_name).file_name() == Some(std::ffi::OsStr::new(file_name))
+        && !file_name.contains(['/', '\\']);
+    if is_plain {
+        Ok(file_name)
+    } else {
+        Err(format!("Invalid export file name: {}", file_name))
+    }
+}
+
+/// Export user (and optionally project) commands into the server's exports directory
+///
+/// There is no save dialog in web mode, so the caller names a new file in
+/// `web_exports_dir`; existing files are never overwritten.
+async fn export_slash_commands(Json(request): Json<CommandExportRequest>) -> ApiResult<usize> {
+    let project_path = match &request.project_id {
+        Some(project_id) => Some(resolve_project_path(project_id).await?),
+        None => None,
+    };
+    let file_name = validate_export_file_name(&request.file_name).map_err(ApiError::bad_request)?;
+    let exports_dir = web_exports_dir().map_err(ApiError::internal)?;
+    let file_path = exports_dir.join(file_name);
+    let compress = request.compress.unwrap_or(false);
+
+    let count = tokio::task::spawn_blocking(move || -> Result<usize, ApiError> {
+        let (bytes, count) =
+            commands::slash_commands::build_command_export(compress, project_path.as_deref())
+                .map_err(ApiError::internal)?;
+        std::fs::create_dir_all(&exports_dir).map_err(|e| {
+            ApiError::internal(format!("Failed to create exports directory: {}", e))
+        })?;
+        let mut file = std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&file_path)
+            .map_err(|e| match e.kind() {
+                std::io::ErrorKind::AlreadyExists => ApiError::new(
+                    StatusCode::CONFLICT,
+                    "conflict",
+                    format!("Export file already exists: {}", file_path.display()),
+                ),
+                _ => ApiError::internal(format!("Failed to create export file: {}", e)),
+            })?;
+        std::io::Write::write_all(&mut file, &bytes)
+            .map_err(|e| ApiError::internal(format!("Failed to write export file: {}", e)))?;
+        Ok(count)
+    })
+    .await
+    .map_err(|e| ApiError::internal(e.to_string()))??;
+    success_response(count)
 }
 
 /// MCP list servers - return empty for web mode
-async fn mcp_list() -> Json<ApiResponse<Vec<serde_json::Value>>> {
-    Json(ApiResponse::success(vec![]))
+async fn mcp_list() -> ApiResult<Vec<serde_json::Value>> {
+    success_response(vec![])
 }
 
 /// Load session history from JSONL file
 async fn load_session_history(
     Path((session_id, project_id)): Path<(String, String)>,
-) -> Json<ApiResponse<Vec<serde_json::Value>>> {
+) -> ApiResult<Vec<serde_json::Value>> {
     match commands::claude::load_session_history(session_id, project_id).await {
-        Ok(history) => Json(ApiResponse::success(history)),
-        Err(e) => Json(ApiResponse::error(e.to_string())),
+        Ok(history) => success_response(history),
+        Err(e) => Err(ApiError::internal(e)),
     }
 }
 
 /// List running Claude sessions
-async fn list_running_claude_sessions() -> Json<ApiResponse<Vec<serde_json::Value>>> {
+async fn list_running_claude_sessions() -> ApiResult<Vec<serde_json::Value>> {
     // Return empty for web mode - no actual Claude processes in web mode
-    Json(ApiResponse::success(vec![]))
+    success_response(vec![])
 }
 
 /// Execute Claude code - mock for web mode
-async fn execute_claude_code() -> Json<ApiResponse<serde_json::Value>> {
-    Json(ApiResponse::error("Claude execution is not available in web mode. Please use the desktop app for running Claude commands.".to_string()))
+async fn execute_claude_code() -> ApiResult<serde_json::Value> {
+    Err(ApiError::not_available(WEB_EXECUTION_UNAVAILABLE))
 }
 
 /// Continue Claude code - mock for web mode
-async fn continue_claude_code() -> Json<ApiResponse<serde_json::Value>> {
-    Json(ApiResponse::error("Claude execution is not available in web mode. Please use the desktop app for running Claude commands.".to_string()))
+async fn continue_claude_code() -> ApiResult<serde_json::Value> {
+    Err(ApiError::not_available(WEB_EXECUTION_UNAVAILABLE))
 }
 
 /// Resume Claude code - mock for web mode  
-async fn resume_claude_code() -> Json<ApiResponse<serde_json::Value>> {
-    Json(ApiResponse::error("Claude execution is not available in web mode. Please use the desktop app for running Claude commands.".to_string()))
+async fn resume_claude_code() -> ApiResult<serde_json::Value> {
+    Err(ApiError::not_available(WEB_EXECUTION_UNAVAILABLE))
 }
 
 /// Cancel Claude execution
-async fn cancel_claude_execution(Path(sessionId): Path<String>) -> Json<ApiResponse<()>> {
+async fn cancel_claude_execution(Path(sessionId): Path<String>) -> ApiResult<()> {
     // In web mode, we don't have a way to cancel the subprocess cleanly
     // The WebSocket closing should handle cleanup
     println!("[TRACE] Cancel request for session: {}", sessionId);
-    Json(ApiResponse::success(()))
+    success_response(())
 }
 
 /// Get Claude session output
-async fn get_claude_session_output(Path(sessionId): Path<String>) -> Json<ApiResponse<String>> {
+async fn get_claude_session_output(Path(sessionId): Path<String>) -> ApiResult<String> {
     // In web mode, output is streamed via WebSocket, not stored
     println!("[TRACE] Output request for session: {}", sessionId);
-    Json(ApiResponse::success(
-        "Output available via WebSocket only".to_string(),
-    ))
+    success_response("Output available via WebSocket only".to_string())
 }
 
 /// WebSocket handler for Claude execution with streaming output
-async fn claude_websocket(ws: WebSocketUpgrade, AxumState(state): AxumState<AppState>) -> Response {
-    ws.on_upgrade(move |socket| claude_websocket_handler(socket, state))
+async fn claude_websocket(
+    ws: WebSocketUpgrade,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    AxumState(state): AxumState<AppState>,
+) -> Response {
+    ws.on_upgrade(move |socket| claude_websocket_handler(socket, state, addr.ip()))
 }
 
-async fn claude_websocket_handler(socket: WebSocket, state: AppState) {
+async fn claude_websocket_handler(socket: WebSocket, state: AppState, client_ip: IpAddr) {
     let (mut sender, mut receiver) = socket.split();
     let session_id = uuid::Uuid::new_v4().to_string();
 
@@ -296,6 +747,36 @@ async fn claude_websocket_handler(socket: WebSocket, state: AppState) {
         );
     });
 
+    // Task to forward process lifecycle events to this WebSocket
+    let mut lifecycle_rx = state.process_registry.subscribe_lifecycle();
+    let lifecycle_state = state.clone();
+    let session_id_for_lifecycle = session_id.clone();
+    let lifecycle_task = tokio::spawn(async move {
+        loop {
+            match lifecycle_rx.recv().await {
+                Ok(event) => {
+                    let message = json!({
+                        "type": "process_lifecycle",
+                        "event": event
+                    });
+                    send_to_session(
+                        &lifecycle_state,
+                        &session_id_for_lifecycle,
+                        message.to_string(),
+                    )
+                    .await;
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                    println!(
+                        "[TRACE] Lifecycle subscriber lagged, skipped {} events",
+                        skipped
+                    );
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+
     // Handle incoming messages from WebSocket
     println!("[TRACE] Starting to listen for WebSocket messages");
     while let Some(msg) = receiver.next().await {
@@ -314,6 +795,21 @@ async fn claude_websocket_handler(socket: WebSocket, state: AppState) {
                         println!("[TRACE] Project path: {}", request.project_path);
                         println!("[TRACE] Prompt length: {} chars", request.prompt.len());
 
+                        // Every message spawns a process, so each one takes a token
+                        if !state.execute_limiter.try_acquire(client_ip) {
+                            println!(
+                                "[TRACE] Rate limit exceeded for {} on /ws/claude",
+                                client_ip
+                            );
+                            let error_msg = json!({
+                                "type": "completion",
+                                "status": "error",
+                                "error": EXECUTE_RATE_LIMITED
+                            });
+                            send_to_session(&state, &session_id, error_msg.to_string()).await;
+                            continue;
+                        }
+
                         // Execute Claude command based on request type
                         let session_id_clone = session_id.clone();
                         let state_clone = state.clone();
@@ -324,13 +820,42 @@ async fn claude_websocket_handler(socket: WebSocket, state: AppState) {
                         );
                         tokio::spawn(async move {
                             println!("[TRACE] Task started for command execution");
+                            let invoked_command = commands::slash_commands::find_invoked_command(
+                                &request.prompt,
+                                Some(request.project_path.clone()),
+                            )
+                            .await;
+                            // Explicit model > slash command frontmatter > default
+                            let model = commands::slash_commands::resolve_command_model(
+                                invoked_command.as_ref(),
+                                request.model.clone(),
+                                "sonnet",
+                            );
+                            // Enforce the tool restrictions declared by the command
+                            let tool_args = invoked_command
+                                .as_ref()
+                                .map(commands::slash_commands::command_tool_args)
+                                .unwrap_or_default();
+                            println!(
+                                "[TRACE] Resolved model: {}, tool args: {:?}",
+                                model, tool_args
+                            );
+                            if let Some(command) = invoked_command {
+                                record_execution_in_background(
+                                    command,
+                                    request.prompt.clone(),
+                                    request.project_path.clone(),
+                                    model.clone(),
+                                );
+                            }
                             let result = match request.command_type.as_str() {
                                 "execute" => {
                                     println!("[TRACE] Calling execute_claude_command");
                                     execute_claude_command(
                                         request.project_path,
                                         request.prompt,
-                                        request.model.unwrap_or_default(),
+                                        model.clone(),
+                                        tool_args.clone(),
                                         session_id_clone.clone(),
                                         state_clone.clone(),
                                     )
@@ -341,7 +866,8 @@ async fn claude_websocket_handler(socket: WebSocket, state: AppState) {
                                     continue_claude_command(
                                         request.project_path,
                                         request.prompt,
-                                        request.model.unwrap_or_default(),
+                                        model.clone(),
+                                        tool_args.clone(),
                                         session_id_clone.clone(),
                                         state_clone.clone(),
                                     )
@@ -353,7 +879,8 @@ async fn claude_websocket_handler(socket: WebSocket, state: AppState) {
                                         request.project_path,
                                         request.session_id.unwrap_or_default(),
                                         request.prompt,
-                                        request.model.unwrap_or_default(),
+                                        model.clone(),
+                                        tool_args.clone(),
                                         session_id_clone.clone(),
                                         state_clone.clone(),
                                     )
@@ -438,6 +965,7 @@ async fn claude_websocket_handler(socket: WebSocket, state: AppState) {
     }
 
     forward_task.abort();
+    lifecycle_task.abort();
     println!("[TRACE] WebSocket handler ended for session {}", session_id);
 }
 
@@ -446,6 +974,7 @@ async fn execute_claude_command(
     project_path: String,
     prompt: String,
     model: String,
+    tool_args: Vec<String>,
     session_id: String,
     state: AppState,
 ) -> Result<(), String> {
@@ -494,6 +1023,7 @@ async fn execute_claude_command(
         "--dangerously-skip-permissions",
     ];
     cmd.args(args);
+    cmd.args(&tool_args);
     cmd.current_dir(&project_path);
     cmd.stdout(std::process::Stdio::piped());
     cmd.stderr(std::process::Stdio::piped());
@@ -511,6 +1041,7 @@ async fn execute_claude_command(
         error
     })?;
     println!("[TRACE] Claude process spawned successfully");
+    let run_id = register_web_process(&state, &child, &session_id, &project_path, &prompt, &model);
 
     // Get stdout for streaming
     let stdout = child.stdout.take().ok_or_else(|| {
@@ -549,6 +1080,7 @@ async fn execute_claude_command(
         println!("[TRACE] Wait error: {}", error);
         error
     })?;
+    finish_web_process(&state, run_id, exit_status.code());
 
     println!(
         "[TRACE] Claude process completed with status: {:?}",
@@ -572,6 +1104,7 @@ async fn continue_claude_command(
     project_path: String,
     prompt: String,
     model: String,
+    tool_args: Vec<String>,
     session_id: String,
     state: AppState,
 ) -> Result<(), String> {
@@ -606,6 +1139,7 @@ async fn continue_claude_command(
         "--verbose",
         "--dangerously-skip-permissions",
     ]);
+    cmd.args(&tool_args);
     cmd.current_dir(&project_path);
     cmd.stdout(std::process::Stdio::piped());
     cmd.stderr(std::process::Stdio::piped());
@@ -614,6 +1148,7 @@ async fn continue_claude_command(
     let mut child = cmd
         .spawn()
         .map_err(|e| format!("Failed to spawn Claude: {}", e))?;
+    let run_id = register_web_process(&state, &child, &session_id, &project_path, &prompt, &model);
     let stdout = child.stdout.take().ok_or("Failed to get stdout")?;
     let stdout_reader = BufReader::new(stdout);
 
@@ -635,6 +1170,7 @@ async fn continue_claude_command(
         .wait()
         .await
         .map_err(|e| format!("Failed to wait for Claude: {}", e))?;
+    finish_web_process(&state, run_id, exit_status.code());
     if !exit_status.success() {
         return Err(format!(
             "Claude execution failed with exit code: {:?}",
@@ -650,6 +1186,7 @@ async fn resume_claude_command(
     claude_session_id: String,
     prompt: String,
     model: String,
+    tool_args: Vec<String>,
     session_id: String,
     state: AppState,
 ) -> Result<(), String> {
@@ -695,6 +1232,7 @@ async fn resume_claude_command(
         "--dangerously-skip-permissions",
     ];
     cmd.args(args);
+    cmd.args(&tool_args);
     cmd.current_dir(&project_path);
     cmd.stdout(std::process::Stdio::piped());
     cmd.stderr(std::process::Stdio::piped());
@@ -712,6 +1250,7 @@ async fn resume_claude_command(
         error
     })?;
     println!("[resume_claude_command] Process spawned successfully");
+    let run_id = register_web_process(&state, &child, &session_id, &project_path, &prompt, &model);
     let stdout = child.stdout.take().ok_or("Failed to get stdout")?;
     let stdout_reader = BufReader::new(stdout);
 
@@ -733,6 +1272,7 @@ async fn resume_claude_command(
         .wait()
         .await
         .map_err(|e| format!("Failed to wait for Claude: {}", e))?;
+    finish_web_process(&state, run_id, exit_status.code());
     if !exit_status.success() {
         return Err(format!(
             "Claude execution failed with exit code: {:?}",
@@ -743,6 +1283,36 @@ async fn resume_claude_command(
     Ok(())
 }
 
+/// Track a spawned Claude process in the registry so lifecycle events are published
+fn register_web_process(
+    state: &AppState,
+    child: &tokio::process::Child,
+    session_id: &str,
+    project_path: &str,
+    prompt: &str,
+    model: &str,
+) -> Option<i64> {
+    state
+        .process_registry
+        .register_claude_session(
+            session_id.to_string(),
+            child.id().unwrap_or(0),
+            project_path.to_string(),
+            prompt.to_string(),
+            model.to_string(),
+        )
+        .map_err(|e| println!("[TRACE] Failed to register process: {}", e))
+        .ok()
+}
+
+/// Publish the exit of a registered Claude process and remove it from the registry
+fn finish_web_process(state: &AppState, run_id: Option<i64>, code: Option<i32>) {
+    if let Some(run_id) = run_id {
+        state.process_registry.notify_exited(run_id, code);
+        let _ = state.process_registry.unregister_process(run_id);
+    }
+}
+
 async fn send_to_session(state: &AppState, session_id: &str, message: String) {
     println!("[TRACE] send_to_session called for session: {}", session_id);
     println!("[TRACE] Message: {}", message);
@@ -767,9 +1337,14 @@ async fn send_to_session(state: &AppState, session_id: &str, message: String) {
 }
 
 /// Create the web server
-pub async fn create_web_server(port: u16) -> Result<(), Box<dyn std::error::Error>> {
+pub async fn create_web_server(config: WebServerConfig) -> Result<(), Box<dyn std::error::Error>> {
+    let port = config.port;
+    // Routes and WebSocket messages that spawn Claude processes share a token-bucket rate limit
+    let limiter = RateLimiter::new(config.execute_rate_limit.clone());
     let state = AppState {
         active_sessions: Arc::new(Mutex::new(std::collections::HashMap::new())),
+        process_registry: Arc::new(crate::process::ProcessRegistry::new()),
+        execute_limiter: limiter.clone(),
     };
 
     // CORS layer to allow requests from phone browsers
@@ -778,6 +1353,26 @@ pub async fn create_web_server(port: u16) -> Result<(), Box<dyn std::error::Erro
         .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
         .allow_headers(Any);
 
+    let execute_routes = Router::new()
+        .route("/api/sessions/execute", get(execute_claude_code))
+        .route("/api/sessions/continue", get(continue_claude_code))
+        .route("/api/sessions/resume", get(resume_claude_code))
+        .route_layer(middleware::from_fn_with_state(
+            limiter.clone(),
+            rate_limit_execute,
+        ));
+
+    // Running slash commands and writing export files also require auth
+    let auth = middleware::from_fn_with_state(config.auth_token.clone(), require_auth);
+    let authed_routes = Router::new()
+        .route(
+            "/api/projects/{project_id}/commands/{name}/execute",
+            post(execute_project_slash_command)
+                .route_layer(middleware::from_fn_with_state(limiter, rate_limit_execute)),
+        )
+        .route("/api/slash-commands/export", post(export_slash_commands))
+        .route_layer(auth);
+
     // Create router with API endpoints
     let app = Router::new()
         // Frontend routes
@@ -786,6 +1381,10 @@ pub async fn create_web_server(port: u16) -> Result<(), Box<dyn std::error::Erro
         // API routes (REST API equivalent of Tauri commands)
         .route("/api/projects", get(get_projects))
         .route("/api/projects/{project_id}/sessions", get(get_sessions))
+        .route(
+            "/api/projects/{project_id}/commands",
+            get(list_project_slash_commands),
+        )
         .route("/api/agents", get(get_agents))
         .route("/api/usage", get(get_usage))
         // Settings and configuration
@@ -808,10 +1407,12 @@ pub async fn create_web_server(port: u16) -> Result<(), Box<dyn std::error::Erro
             get(load_session_history),
         )
         .route("/api/sessions/running", get(list_running_claude_sessions))
-        // Claude execution endpoints (read-only in web mode)
-        .route("/api/sessions/execute", get(execute_claude_code))
-        .route("/api/sessions/continue", get(continue_claude_code))
-        .route("/api/sessions/resume", get(resume_claude_code))
+        // Claude execution endpoints (rate limited, see execute_routes)
+        .merge(execute_routes)
+        // Slash command execution and exports (authenticated, see authed_routes)
+        .merge(authed_routes)
+        // WebSocket endpoint for real-time Claude execution, limited per message
+        .route("/ws/claude", get(claude_websocket))
         .route(
             "/api/sessions/{sessionId}/cancel",
             get(cancel_claude_execution),
@@ -820,8 +1421,6 @@ pub async fn create_web_server(port: u16) -> Result<(), Box<dyn std::error::Erro
             "/api/sessions/{sessionId}/output",
             get(get_claude_session_output),
         )
-        // WebSocket endpoint for real-time Claude execution
-        .route("/ws/claude", get(claude_websocket))
         // Serve static assets
         .nest_service("/assets", ServeDir::new("../dist/assets"))
         .nest_service("/vite.svg", ServeDir::new("../dist/vite.svg"))
@@ -833,15 +1432,32 @@ pub async fn create_web_server(port: u16) -> Result<(), Box<dyn std::error::Erro
     println!("📱 Access from phone: http://YOUR_PC_IP:{}", port);
 
     let listener = TcpListener::bind(addr).await?;
-    axum::serve(listener, app).await?;
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await?;
 
     Ok(())
 }
 
 /// Start web server mode (alternative to Tauri GUI)
 pub async fn start_web_mode(port: Option<u16>) -> Result<(), Box<dyn std::error::Error>> {
-    let port = port.unwrap_or(8080);
+    let mut config = WebServerConfig::default();
+    if let Some(port) = port {
+        config.port = port;
+    }
+    config.auth_token = std::env::var(WEB_AUTH_TOKEN_ENV)
+        .ok()
+        .map(|token| token.trim().to_string())
+        .filter(|token| !token.is_empty());
+    if config.auth_token.is_none() {
+        println!(
+            "🔒 {} is not set; slash command execution and exports are limited to localhost",
+            WEB_AUTH_TOKEN_ENV
+        );
+    }
 
     println!("🚀 Starting Opcode in web server mode...");
-    create_web_server(port).await
+    create_web_server(config).await
 }
@@ -1,7 +1,8 @@
-import { useState, useEffect } from "react";
+import { useState, useEffect, useRef } from "react";
+import { listen } from "@tauri-apps/api/event";
 import { motion } from "framer-motion";
 import { Bot, FolderCode } from "lucide-react";
-import { api, type Project, type Session, type ClaudeMdFile } from "@/lib/api";
+import { api, type Project, type Session, type ClaudeMdFile, type ClaudePathConflict } from "@/lib/api";
 import { initializeWebMode } from "@/lib/apiAdapter";
 import { OutputCacheProvider } from "@/lib/outputCache";
 import { TabProvider } from "@/contexts/TabContext";
@@ -59,7 +60,7 @@ function AppContent() {
   const [showClaudeBinaryDialog, setShowClaudeBinaryDialog] = useState(false);
   const [showProjectPicker, setShowProjectPicker] = useState(false);
   const [homeDirectory, setHomeDirectory] = useState<string>('/');
-  const [toast, setToast] = useState<{ message: string; type: "success" | "error" | "info" } | null>(null);
+  const [toast, setToast] = useState<{ message: string; type: "success" | "error" | "info"; duration?: number } | null>(null);
   const [projectForSettings, setProjectForSettings] = useState<Project | null>(null);
   const [previousView] = useState<View>("welcome");
   
@@ -88,6 +89,44 @@ function AppContent() {
     initializeWebMode();
   }, []);
 
+  // Warn when the shell's claude differs from the one opcode runs. The startup
+  // event can fire before this listener exists, so also ask once after mount.
+  const shownPathConflict = useRef<string | null>(null);
+  useEffect(() => {
+    const showConflict = (conflict: ClaudePathConflict | null) => {
+      if (!conflict) return;
+      const key = `${conflict.shell_path}|${conflict.selected_path}`;
+      if (shownPathConflict.current === key) return;
+      shownPathConflict.current = key;
+      setToast({
+        message: `Your shell runs claude from ${conflict.shell_path}, but opcode uses ${conflict.selected_path}`,
+        type: "info",
+        duration: 10000,
+      });
+    };
+
+    let unlisten: (() => void) | undefined;
+    let cancelled = false;
+    listen<ClaudePathConflict>("claude-path-conflict", (event) => showConflict(event.payload))
+      .then((fn) => {
+        if (cancelled) fn();
+        else unlisten = fn;
+      })
+      .catch(() => {
+        // Events are not available in web mode
+      });
+    api.detectClaudePathConflict()
+      .then((conflict) => {
+        if (!cancelled) showConflict(conflict);
+      })
+      .catch(() => {});
+
+    return () => {
+      cancelled = true;
+      unlisten?.();
+    };
+  }, []);
+
   // Load projects on mount when in projects view
   useEffect(() => {
     if (view === "projects") {
@@ -452,6 +491,7 @@ function AppContent() {
           <Toast
             message={toast.message}
             type={toast.type}
+            duration={toast.duration}
             onDismiss={() => setToast(null)}
           />
         )}
@@ -51,7 +51,7 @@ export const ClaudeCodeSession: React.FC<ClaudeCodeSessionProps> = ({
   const [showSlashCommandsSettings, setShowSlashCommandsSettings] = useState(false);
   const [forkCheckpointId, setForkCheckpointId] = useState<string | null>(null);
   const [forkSessionName, setForkSessionName] = useState("");
-  const [queuedPrompts, setQueuedPrompts] = useState<Array<{ id: string; prompt: string; model: "sonnet" | "opus" }>>([]);
+  const [queuedPrompts, setQueuedPrompts] = useState<Array<{ id: string; prompt: string; model: "sonnet" | "opus"; modelOverridden: boolean }>>([]);
   const [showPreview, setShowPreview] = useState(false);
   const [previewUrl, setPreviewUrl] = useState<string | null>(null);
   const [isPreviewMaximized, setIsPreviewMaximized] = useState(false);
@@ -106,10 +106,10 @@ export const ClaudeCodeSession: React.FC<ClaudeCodeSessionProps> = ({
   };
 
   // Handle sending prompts
-  const handleSendPrompt = useCallback(async (prompt: string, model: "sonnet" | "opus") => {
+  const handleSendPrompt = useCallback(async (prompt: string, model: "sonnet" | "opus", modelOverridden: boolean) => {
     console.log('[TRACE] handleSendPrompt called:');
     console.log('[TRACE]   prompt length:', prompt.length);
-    console.log('[TRACE]   model:', model);
+    console.log('[TRACE]   model:', model, modelOverridden ? '(chosen)' : '(default)');
     console.log('[TRACE]   projectPath:', projectPath);
     console.log('[TRACE]   isStreaming:', isStreaming);
     console.log('[TRACE]   isFirstPrompt:', isFirstPrompt);
@@ -124,22 +124,26 @@ export const ClaudeCodeSession: React.FC<ClaudeCodeSessionProps> = ({
     if (isStreaming) {
       console.log('[TRACE] Currently streaming - adding to queue');
       const id = Date.now().toString();
-      setQueuedPrompts(prev => [...prev, { id, prompt, model }]);
+      setQueuedPrompts(prev => [...prev, { id, prompt, model, modelOverridden }]);
       return;
     }
 
+    // Leave the model unset unless the user picked one, so a slash command's
+    // frontmatter model takes precedence over the picker's default
+    const modelOverride = modelOverridden ? model : undefined;
+
     try {
       console.log('[TRACE] Clearing error and starting prompt execution');
       setError(null);
       
       if (isFirstPrompt) {
         console.log('[TRACE] First prompt - calling api.executeClaudeCode');
-        await api.executeClaudeCode(projectPath, prompt, model);
+        await api.executeClaudeCode(projectPath, prompt, modelOverride);
         setIsFirstPrompt(false);
         console.log('[TRACE] executeClaudeCode completed');
       } else if (claudeSessionId) {
         console.log('[TRACE] Continue prompt - calling api.continueClaudeCode');
-        await api.continueClaudeCode(projectPath, prompt, model);
+        await api.continueClaudeCode(projectPath, prompt, modelOverride);
         console.log('[TRACE] continueClaudeCode completed');
       } else {
         console.log('[TRACE] No claude session ID for continue');
@@ -157,7 +161,7 @@ export const ClaudeCodeSession: React.FC<ClaudeCodeSessionProps> = ({
     const nextPrompt = queuedPrompts[0];
     setQueuedPrompts(prev => prev.slice(1));
     
-    await handleSendPrompt(nextPrompt.prompt, nextPrompt.model);
+    await handleSendPrompt(nextPrompt.prompt, nextPrompt.model, nextPrompt.modelOverridden);
   }, [queuedPrompts, isStreaming, handleSendPrompt]);
 
   // Effect to process queue when streaming stops
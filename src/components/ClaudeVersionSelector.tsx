@@ -238,7 +238,7 @@ export const ClaudeVersionSelector: React.FC<ClaudeVersionSelectorProps> = ({
                       <div className="flex-1">
                         <div className="font-mono text-sm">{installation.path}</div>
                         <div className="flex items-center gap-2 text-xs text-muted-foreground">
-                          <span>{installation.version || "Unknown version"}</span>
+                          <span>{installation.version || (installation.version_check_timed_out ? "Version check timed out" : "Unknown version")}</span>
                           <span>•</span>
                           <span>{installation.source}</span>
                           <Badge variant={getInstallationTypeColor(installation)} className="text-xs ml-2">
@@ -307,7 +307,7 @@ export const ClaudeVersionSelector: React.FC<ClaudeVersionSelectorProps> = ({
                         <div className="flex-1 min-w-0">
                           <div className="font-medium truncate">{installation.path}</div>
                           <div className="text-xs text-muted-foreground">
-                            {installation.version || "Version unknown"} • {installation.source}
+                            {installation.version || (installation.version_check_timed_out ? "Version check timed out" : "Version unknown")} • {installation.source}
                           </div>
                         </div>
                         <Badge variant="outline" className="text-xs">
@@ -329,7 +329,7 @@ export const ClaudeVersionSelector: React.FC<ClaudeVersionSelectorProps> = ({
                         <div className="flex-1 min-w-0">
                           <div className="font-medium truncate">{installation.path}</div>
                           <div className="text-xs text-muted-foreground">
-                            {installation.version || "Version unknown"} • {installation.source}
+                            {installation.version || (installation.version_check_timed_out ? "Version check timed out" : "Version unknown")} • {installation.source}
                           </div>
                         </div>
                         <Badge variant="outline" className="text-xs">
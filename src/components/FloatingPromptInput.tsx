@@ -39,9 +39,10 @@ const getCurrentWebviewWindow = tauriGetCurrentWebviewWindow || (() => ({ listen
 
 interface FloatingPromptInputProps {
   /**
-   * Callback when prompt is sent
+   * Callback when prompt is sent. `modelOverridden` is false until the user picks
+   * a model, so a slash command's frontmatter model can take effect.
    */
-  onSend: (prompt: string, model: "sonnet" | "opus") => void;
+  onSend: (prompt: string, model: "sonnet" | "opus", modelOverridden: boolean) => void;
   /**
    * Whether the input is loading
    */
@@ -207,7 +208,7 @@ const MODELS: Model[] = [
  * const promptRef = useRef<FloatingPromptInputRef>(null);
  * <FloatingPromptInput
  *   ref={promptRef}
- *   onSend={(prompt, model) => console.log('Send:', prompt, model)}
+ *   onSend={(prompt, model, modelOverridden) => console.log('Send:', prompt, model, modelOverridden)}
  *   isLoading={false}
  * />
  */
@@ -226,6 +227,7 @@ const FloatingPromptInputInner = (
 ) => {
   const [prompt, setPrompt] = useState("");
   const [selectedModel, setSelectedModel] = useState<"sonnet" | "opus">(defaultModel);
+  const [modelOverridden, setModelOverridden] = useState(false);
   const [selectedThinkingMode, setSelectedThinkingMode] = useState<ThinkingMode>("auto");
   const [isExpanded, setIsExpanded] = useState(false);
   const [modelPickerOpen, setModelPickerOpen] = useState(false);
@@ -706,7 +708,7 @@ const FloatingPromptInputInner = (
         finalPrompt = `${finalPrompt}.\n\n${thinkingMode.phrase}.`;
       }
 
-      onSend(finalPrompt, selectedModel);
+      onSend(finalPrompt, selectedModel, modelOverridden);
       setPrompt("");
       setEmbeddedImages([]);
       setTextareaHeight(48); // Reset height after sending
@@ -936,6 +938,7 @@ const FloatingPromptInputInner = (
                               key={model.id}
                               onClick={() => {
                                 setSelectedModel(model.id);
+                                setModelOverridden(true);
                                 setModelPickerOpen(false);
                               }}
                               className={cn(
@@ -1119,6 +1122,7 @@ const FloatingPromptInputInner = (
                         key={model.id}
                         onClick={() => {
                           setSelectedModel(model.id);
+                          setModelOverridden(true);
                           setModelPickerOpen(false);
                         }}
                         className={cn(
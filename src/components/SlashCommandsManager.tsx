@@ -26,7 +26,7 @@ import { Card } from "@/components/ui/card";
 import { Badge } from "@/components/ui/badge";
 import { Select, SelectContent, SelectItem, SelectTrigger, SelectValue } from "@/components/ui/select";
 import { Dialog, DialogContent, DialogHeader, DialogTitle, DialogFooter } from "@/components/ui/dialog";
-import { api, type SlashCommand } from "@/lib/api";
+import { api, asCommandError, type SlashCommand } from "@/lib/api";
 import { cn } from "@/lib/utils";
 import { COMMON_TOOL_MATCHERS } from "@/types/hooks";
 import { useTrackEvent } from "@/hooks";
@@ -177,7 +177,9 @@ export const SlashCommandsManager: React.FC<SlashCommandsManagerProps> = ({
         commandForm.content,
         commandForm.description || undefined,
         commandForm.allowedTools,
-        commandForm.scope === 'project' ? projectPath : undefined
+        commandForm.scope === 'project' ? projectPath : undefined,
+        // Only edits may replace an existing file; a new command must not clobber one
+        editingCommand !== null
       );
       
       // Track command creation
@@ -190,7 +192,12 @@ export const SlashCommandsManager: React.FC<SlashCommandsManagerProps> = ({
       await loadCommands();
     } catch (err) {
       console.error("Failed to save command:", err);
-      setError(err instanceof Error ? err.message : "Failed to save command");
+      const commandError = asCommandError(err);
+      if (commandError?.code === "already_exists") {
+        setError(`A command named "${commandForm.name}" already exists. Choose another name or edit the existing command.`);
+      } else {
+        setError(commandError?.message ?? (err instanceof Error ? err.message : "Failed to save command"));
+      }
     } finally {
       setSaving(false);
     }
@@ -213,8 +220,15 @@ export const SlashCommandsManager: React.FC<SlashCommandsManagerProps> = ({
       await loadCommands();
     } catch (err) {
       console.error("Failed to delete command:", err);
-      const errorMessage = err instanceof Error ? err.message : "Failed to delete command";
-      setError(errorMessage);
+      const commandError = asCommandError(err);
+      if (commandError?.code === "not_found") {
+        // Already gone, e.g. deleted from disk; just refresh the list
+        setDeleteDialogOpen(false);
+        setCommandToDelete(null);
+        await loadCommands();
+      } else {
+        setError(commandError?.message ?? (err instanceof Error ? err.message : "Failed to delete command"));
+      }
     } finally {
       setDeleting(false);
     }
@@ -1,9 +1,9 @@
-import { apiCall } from './apiAdapter';
+import { apiCall, isStructuredError } from './apiAdapter';
 import type { HooksConfiguration } from '@/types/hooks';
 
 /** Process type for tracking in ProcessRegistry */
 export type ProcessType = 
-  | { AgentRun: { agent_id: number; agent_name: string } }
+  | { AgentRun: { agent_id: number | null; agent_name: string } }
   | { ClaudeSession: { session_id: string } };
 
 /** Information about a running process */
@@ -105,10 +105,26 @@ export interface ClaudeInstallation {
   path: string;
   /** Version string if available */
   version?: string;
+  /** The version check timed out, so the version is unknown rather than missing */
+  version_check_timed_out?: boolean;
   /** Source of discovery (e.g., "nvm", "system", "homebrew", "which") */
   source: string;
   /** Type of installation */
   installation_type: "System" | "Custom";
+  /** Node.js version for Node-based installs, when it could be determined */
+  node_version?: string | null;
+}
+
+/**
+ * Raised when the shell's `claude` is not the installation opcode runs
+ */
+export interface ClaudePathConflict {
+  /** Binary the user's shell resolves for `claude` */
+  shell_path: string;
+  shell_version?: string | null;
+  /** Binary opcode runs */
+  selected_path: string;
+  selected_version?: string | null;
 }
 
 // Agent API types
@@ -120,6 +136,7 @@ export interface Agent {
   default_task?: string;
   model: string;
   hooks?: string; // JSON string of HooksConfiguration
+  env?: string; // JSON object of environment variables
   created_at: string;
   updated_at: string;
 }
@@ -147,15 +164,18 @@ export interface GitHubAgentFile {
 
 export interface AgentRun {
   id?: number;
-  agent_id: number;
+  /** Null for ephemeral runs that have no stored agent */
+  agent_id: number | null;
   agent_name: string;
   agent_icon: string;
   task: string;
   model: string;
   project_path: string;
   session_id: string;
-  status: string; // 'pending', 'running', 'completed', 'failed', 'cancelled'
+  status: string; // 'pending', 'queued', 'running', 'rate_limited', 'completed', 'failed', 'cancelled'
   pid?: number;
+  /** Times the run was relaunched after an API rate limit */
+  retry_count?: number;
   process_started_at?: string;
   created_at: string;
   completed_at?: string;
@@ -170,15 +190,18 @@ export interface AgentRunMetrics {
 
 export interface AgentRunWithMetrics {
   id?: number;
-  agent_id: number;
+  /** Null for ephemeral runs that have no stored agent */
+  agent_id: number | null;
   agent_name: string;
   agent_icon: string;
   task: string;
   model: string;
   project_path: string;
   session_id: string;
-  status: string; // 'pending', 'running', 'completed', 'failed', 'cancelled'
+  status: string; // 'pending', 'queued', 'running', 'rate_limited', 'completed', 'failed', 'cancelled'
   pid?: number;
+  /** Times the run was relaunched after an API rate limit */
+  retry_count?: number;
   duration_ms?: number;
   total_tokens?: number;
   process_started_at?: string;
@@ -417,6 +440,28 @@ export interface SlashCommand {
   accepts_arguments: boolean;
 }
 
+/**
+ * Error returned by the slash command APIs, with a stable `code` to branch on
+ */
+export interface CommandError {
+  code:
+    | "not_found"
+    | "already_exists"
+    | "invalid_name"
+    | "invalid_input"
+    | "content_mismatch"
+    | "io"
+    | "internal";
+  message: string;
+}
+
+/**
+ * Returns the `CommandError` a rejected slash command API call carries, if any
+ */
+export function asCommandError(error: unknown): CommandError | null {
+  return isStructuredError(error) ? (error as CommandError) : null;
+}
+
 /**
  * Result of adding a server
  */
@@ -442,6 +487,8 @@ export interface ImportServerResult {
   name: string;
   success: boolean;
   error?: string;
+  /** Changes and warnings from normalizing env values */
+  notes?: string[];
 }
 
 /**
@@ -700,6 +747,7 @@ export const api = {
    * @param default_task - Optional default task
    * @param model - Optional model (defaults to 'sonnet')
    * @param hooks - Optional hooks configuration as JSON string
+   * @param env - Optional environment variables as a JSON object string
    * @returns Promise resolving to the created agent
    */
   async createAgent(
@@ -708,7 +756,8 @@ export const api = {
     system_prompt: string, 
     default_task?: string, 
     model?: string,
-    hooks?: string
+    hooks?: string,
+    env?: string
   ): Promise<Agent> {
     try {
       return await apiCall<Agent>('create_agent', { 
@@ -717,7 +766,8 @@ export const api = {
         systemPrompt: system_prompt,
         defaultTask: default_task,
         model,
-        hooks
+        hooks,
+        env
       });
     } catch (error) {
       console.error("Failed to create agent:", error);
@@ -734,6 +784,7 @@ export const api = {
    * @param default_task - Optional default task
    * @param model - Optional model
    * @param hooks - Optional hooks configuration as JSON string
+   * @param env - Optional environment variables as a JSON object string; omitted keeps the current value
    * @returns Promise resolving to the updated agent
    */
   async updateAgent(
@@ -743,7 +794,8 @@ export const api = {
     system_prompt: string, 
     default_task?: string, 
     model?: string,
-    hooks?: string
+    hooks?: string,
+    env?: string
   ): Promise<Agent> {
     try {
       return await apiCall<Agent>('update_agent', { 
@@ -753,7 +805,8 @@ export const api = {
         systemPrompt: system_prompt,
         defaultTask: default_task,
         model,
-        hooks
+        hooks,
+        env
       });
     } catch (error) {
       console.error("Failed to update agent:", error);
@@ -1028,21 +1081,21 @@ export const api = {
   /**
    * Executes a new interactive Claude Code session with streaming output
    */
-  async executeClaudeCode(projectPath: string, prompt: string, model: string): Promise<void> {
+  async executeClaudeCode(projectPath: string, prompt: string, model?: string): Promise<void> {
     return apiCall("execute_claude_code", { projectPath, prompt, model });
   },
 
   /**
    * Continues an existing Claude Code conversation with streaming output
    */
-  async continueClaudeCode(projectPath: string, prompt: string, model: string): Promise<void> {
+  async continueClaudeCode(projectPath: string, prompt: string, model?: string): Promise<void> {
     return apiCall("continue_claude_code", { projectPath, prompt, model });
   },
 
   /**
    * Resumes an existing Claude Code session by ID with streaming output
    */
-  async resumeClaudeCode(projectPath: string, sessionId: string, prompt: string, model: string): Promise<void> {
+  async resumeClaudeCode(projectPath: string, sessionId: string, prompt: string, model?: string): Promise<void> {
     return apiCall("resume_claude_code", { projectPath, sessionId, prompt, model });
   },
 
@@ -1475,9 +1528,9 @@ export const api = {
   /**
    * Imports MCP servers from Claude Desktop
    */
-  async mcpAddFromClaudeDesktop(scope: string = "local"): Promise<ImportResult> {
+  async mcpAddFromClaudeDesktop(scope: string = "local", expandEnv?: boolean): Promise<ImportResult> {
     try {
-      return await apiCall<ImportResult>("mcp_add_from_claude_desktop", { scope });
+      return await apiCall<ImportResult>("mcp_add_from_claude_desktop", { scope, expandEnv });
     } catch (error) {
       console.error("Failed to import from Claude Desktop:", error);
       throw error;
@@ -1596,6 +1649,19 @@ export const api = {
     }
   },
 
+  /**
+   * Compare the shell's `claude` against the installation opcode selected
+   * @returns Promise resolving to the conflict, or null when they match
+   */
+  async detectClaudePathConflict(): Promise<ClaudePathConflict | null> {
+    try {
+      return await apiCall<ClaudePathConflict | null>("detect_claude_path_conflict");
+    } catch (error) {
+      console.error("Failed to detect Claude path conflict:", error);
+      throw error;
+    }
+  },
+
   // Storage API methods
 
   /**
@@ -1900,6 +1966,7 @@ export const api = {
    * @param description - Optional description
    * @param allowedTools - List of allowed tools for this command
    * @param projectPath - Required for project scope commands
+   * @param overwrite - When false, an existing command rejects with code "already_exists"
    * @returns Promise resolving to the saved command
    */
   async slashCommandSave(
@@ -1909,7 +1976,8 @@ export const api = {
     content: string,
     description: string | undefined,
     allowedTools: string[],
-    projectPath?: string
+    projectPath?: string,
+    overwrite?: boolean
   ): Promise<SlashCommand> {
     try {
       return await apiCall<SlashCommand>("slash_command_save", {
@@ -1919,7 +1987,8 @@ export const api = {
         content,
         description,
         allowedTools,
-        projectPath
+        projectPath,
+        overwrite
       });
     } catch (error) {
       console.error("Failed to save slash command:", error);
@@ -51,12 +51,32 @@ function detectEnvironment(): boolean {
 }
 
 /**
- * Response wrapper for REST API calls
+ * Error body returned by the web server on failure
+ */
+interface ApiErrorBody {
+  code: string;
+  message: string;
+  details?: unknown;
+}
+
+/**
+ * Response envelope for REST API calls: `{ data }` on success, `{ error }` on failure
  */
 interface ApiResponse<T> {
-  success: boolean;
   data?: T;
-  error?: string;
+  error?: ApiErrorBody;
+}
+
+/**
+ * Whether an error carries the `{ code, message }` shape commands return
+ */
+export function isStructuredError(error: unknown): error is { code: string; message: string } {
+  return (
+    typeof error === 'object' &&
+    error !== null &&
+    typeof (error as { code?: unknown }).code === 'string' &&
+    typeof (error as { message?: unknown }).message === 'string'
+  );
 }
 
 /**
@@ -111,14 +131,22 @@ async function restApiCall<T>(endpoint: string, params?: any): Promise<T> {
       },
     });
 
-    if (!response.ok) {
+    let result: ApiResponse<T>;
+    try {
+      result = await response.json();
+    } catch {
       throw new Error(`HTTP error! status: ${response.status}`);
     }
 
-    const result: ApiResponse<T> = await response.json();
-    
-    if (!result.success) {
-      throw new Error(result.error || 'API call failed');
+    if (result.error) {
+      // Keep the machine-readable code so callers can branch on it
+      throw Object.assign(
+        new Error(result.error.message || `API call failed (${result.error.code})`),
+        { code: result.error.code }
+      );
+    }
+    if (!response.ok) {
+      throw new Error(`HTTP error! status: ${response.status}`);
     }
 
     return result.data as T;
@@ -140,6 +168,10 @@ export async function apiCall<T>(command: string, params?: any): Promise<T> {
     try {
       return await invoke<T>(command, params);
     } catch (error) {
+      // A structured `{ code, message }` error came from the command itself, not the transport
+      if (isStructuredError(error)) {
+        throw error;
+      }
       console.warn(`[Tauri] invoke failed, falling back to web mode:`, error);
       // Fall through to web mode
     }